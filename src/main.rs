@@ -1,13 +1,28 @@
 mod model;
 mod view;
 
-use model::exp::scoremap::ScoremapError;
+use model::exp::{
+  course::{Course, CourseError},
+  scoremap::ScoremapError,
+  sentence::{
+    roman::{
+      InputScheme, RomanBaseStyle, RomanPreference,
+      RomanPreferenceError,
+    },
+    InputMethod,
+  },
+};
+use model::settings::{Settings, SETTINGS_PATH};
 use std::{fs::File, path::Path};
-use view::ViewError;
+use view::{Setlist, SetlistSong, ViewError};
 
 #[derive(Debug)]
 pub enum EntireError {
   Scoremap(ScoremapError),
+  Course(CourseError),
+  RomanPreference(RomanPreferenceError),
+  FileRead { reason: String },
+  MissingArgValue { name: String },
   View(ViewError),
 }
 
@@ -17,19 +32,134 @@ impl From<ScoremapError> for EntireError {
   }
 }
 
+impl From<CourseError> for EntireError {
+  fn from(err: CourseError) -> Self {
+    EntireError::Course(err)
+  }
+}
+
+impl From<RomanPreferenceError> for EntireError {
+  fn from(err: RomanPreferenceError) -> Self {
+    EntireError::RomanPreference(err)
+  }
+}
+
 impl From<ViewError> for EntireError {
   fn from(err: ViewError) -> Self {
     EntireError::View(err)
   }
 }
 
+fn open_file(path: &Path) -> Result<File, EntireError> {
+  File::open(path).map_err(|err| EntireError::FileRead {
+    reason: format!("{}: {}", path.display(), err),
+  })
+}
+
+// 方式名 (hepburn, kunrei, default)、入力方式名 (azik, act)、
+// または好みを書いたファイルのパスを受け付ける
+fn load_roman_preference(
+  arg: &str,
+) -> Result<RomanPreference, EntireError> {
+  if let Ok(base) = arg.parse::<RomanBaseStyle>() {
+    return Ok(RomanPreference::new(base));
+  }
+  if let Ok(scheme) = arg.parse::<InputScheme>() {
+    return Ok(RomanPreference::default().scheme(scheme));
+  }
+  let profile = std::fs::read_to_string(arg).map_err(|err| {
+    EntireError::FileRead {
+      reason: format!("{}: {}", arg, err),
+    }
+  })?;
+  Ok(profile.parse()?)
+}
+
+// 値を取るオプションの後ろに値がない、または別のオプションが続くときはエラーにする
+fn arg_value<'a>(
+  args: &'a [String],
+  name: &str,
+) -> Result<Option<&'a String>, EntireError> {
+  let index = match args.iter().position(|arg| arg == name) {
+    Some(index) => index,
+    None => return Ok(None),
+  };
+  match args.get(index + 1) {
+    Some(value) if !value.starts_with("--") => Ok(Some(value)),
+    _ => Err(EntireError::MissingArgValue { name: name.into() }),
+  }
+}
+
 fn main() -> Result<(), EntireError> {
   use model::exp::scoremap::Scoremap;
-  let score = Scoremap::from_file(
-    File::open(Path::new("score/sampleScore.tsc")).unwrap(),
-    |config| config.ignore_unsupported_property(true),
-  )?;
+  let args: Vec<String> = std::env::args().collect();
+  let practice = args.iter().any(|arg| arg == "--practice");
+  let input_method = if args.iter().any(|arg| arg == "--kana") {
+    InputMethod::Kana
+  } else {
+    InputMethod::Roman
+  };
+  let calibrate = args.iter().any(|arg| arg == "--calibrate");
+  let course_path = arg_value(&args, "--course")?.map(Path::new);
+  let export_dir = arg_value(&args, "--export")?.map(Into::into);
+  let roman_preference = match arg_value(&args, "--roman")? {
+    Some(arg) => load_roman_preference(arg)?,
+    // 指定がなければ、設定画面で選んだ方式にする
+    None => RomanPreference::new(
+      Settings::load(Path::new(SETTINGS_PATH))
+        .map(|settings| settings.roman_style)
+        .unwrap_or(RomanBaseStyle::Default),
+    ),
+  };
+
+  let setlist = if let Some(course_path) = course_path {
+    let course = Course::from_file(open_file(course_path)?)?;
+    // 譜面のパスは、コースファイルのあるディレクトリから辿る
+    let course_dir = course_path.parent().unwrap_or(Path::new(""));
+    let mut songs = vec![];
+    for entry in course.entries {
+      let score = Scoremap::from_file(
+        open_file(&course_dir.join(&entry.score_path))?,
+        |config| {
+          config
+            .ignore_unsupported_property(
+              entry.ignore_unsupported_property,
+            )
+            .roman_preference(roman_preference.clone())
+        },
+      )?;
+      songs.push(SetlistSong {
+        score,
+        practice: practice || entry.practice,
+      });
+    }
+    Setlist {
+      title: Some(course.title),
+      life: course.life,
+      songs,
+      input_method,
+      calibrate,
+      export_dir,
+    }
+  } else {
+    let score = Scoremap::from_file(
+      File::open(Path::new("score/sampleScore.tsc")).unwrap(),
+      |config| {
+        config
+          .ignore_unsupported_property(true)
+          .roman_preference(roman_preference.clone())
+      },
+    )?;
+    Setlist {
+      title: None,
+      life: None,
+      songs: vec![SetlistSong { score, practice }],
+      input_method,
+      calibrate,
+      export_dir,
+    }
+  };
 
-  view::run_router(score)?;
+  view::run_router(setlist)?;
   Ok(())
 }
@@ -1,2 +1,5 @@
 pub mod exp;
 pub mod game;
+pub mod keyboard_layout;
+pub mod settings;
+pub mod skin;
@@ -1,3 +1,4 @@
+pub mod course;
 pub mod game_activity;
 pub mod scoremap;
 pub mod time;
@@ -0,0 +1,163 @@
+use std::{
+  fs::File,
+  io::{BufRead, BufReader, Read},
+  str::FromStr,
+};
+
+#[derive(Debug)]
+pub enum CourseError {
+  FileReadError {
+    reason: String,
+  },
+  InvalidLine {
+    line_num: usize,
+    reason: &'static str,
+  },
+  EmptyCourse,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseEntry {
+  pub score_path: String,
+  pub ignore_unsupported_property: bool,
+  pub practice: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Course {
+  pub title: String,
+  pub life: Option<u32>,
+  pub entries: Vec<CourseEntry>,
+}
+
+impl FromStr for Course {
+  type Err = CourseError;
+
+  fn from_str(string: &str) -> Result<Self, Self::Err> {
+    Self::from_reader(BufReader::new(string.as_bytes()))
+  }
+}
+
+impl Course {
+  pub fn from_file(file: File) -> Result<Self, CourseError> {
+    Self::from_reader(BufReader::new(file))
+  }
+
+  fn from_reader<T: Read>(
+    reader: BufReader<T>,
+  ) -> Result<Self, CourseError> {
+    use CourseError::*;
+
+    let mut course = Course {
+      title: "コース".into(),
+      life: None,
+      entries: vec![],
+    };
+    for (line_num, line) in reader.lines().enumerate() {
+      let line_num = line_num + 1; // starts from 1
+      let line = line.map_err(|err| FileReadError {
+        reason: err.to_string(),
+      })?;
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      if let Some(property) = line.strip_prefix(':') {
+        let (key, value) = property
+          .split_once(char::is_whitespace)
+          .ok_or(InvalidLine {
+          line_num,
+          reason: "プロパティの指定が正しくありません。",
+        })?;
+        match key {
+          "title" => course.title = value.trim().into(),
+          "life" => {
+            course.life =
+              Some(value.trim().parse().map_err(|_| InvalidLine {
+                line_num,
+                reason: "ライフには整数を指定してください。",
+              })?)
+          }
+          _ => {
+            return Err(InvalidLine {
+              line_num,
+              reason: "未対応のプロパティです。",
+            })
+          }
+        }
+        continue;
+      }
+      let mut words = line.split_whitespace();
+      let mut entry = CourseEntry {
+        score_path: words.next().unwrap_or_default().into(),
+        ignore_unsupported_property: false,
+        practice: false,
+      };
+      for option in words {
+        match option {
+          "ignore_unsupported_property" => {
+            entry.ignore_unsupported_property = true
+          }
+          "practice" => entry.practice = true,
+          _ => {
+            return Err(InvalidLine {
+              line_num,
+              reason: "未対応の譜面オプションです。",
+            })
+          }
+        }
+      }
+      course.entries.push(entry);
+    }
+    if course.entries.is_empty() {
+      return Err(EmptyCourse);
+    }
+    Ok(course)
+  }
+}
+
+#[test]
+fn tournament() -> Result<(), CourseError> {
+  let course: Course = r#"
+# 大会用
+:title 予選コース
+:life 100
+
+score/first.tsc
+score/second.tsc ignore_unsupported_property
+score/third.tsc practice ignore_unsupported_property
+"#
+  .parse()?;
+  assert_eq!("予選コース", course.title);
+  assert_eq!(Some(100), course.life);
+  assert_eq!(
+    vec![
+      CourseEntry {
+        score_path: "score/first.tsc".into(),
+        ignore_unsupported_property: false,
+        practice: false,
+      },
+      CourseEntry {
+        score_path: "score/second.tsc".into(),
+        ignore_unsupported_property: true,
+        practice: false,
+      },
+      CourseEntry {
+        score_path: "score/third.tsc".into(),
+        ignore_unsupported_property: true,
+        practice: true,
+      },
+    ],
+    course.entries
+  );
+
+  assert!(matches!(
+    ":title 空".parse::<Course>(),
+    Err(CourseError::EmptyCourse)
+  ));
+  assert!(matches!(
+    "score/first.tsc fast".parse::<Course>(),
+    Err(CourseError::InvalidLine { line_num: 1, .. })
+  ));
+  Ok(())
+}
@@ -1,7 +1,7 @@
 use super::{
   note::{sentence::Sentence, Note, NoteContent, NoteId, TypeResult},
   scoremap::sections::Sections,
-  section::Section,
+  section::{Section, SectionStats},
   time::Seconds,
 };
 
@@ -17,6 +17,9 @@ pub struct GameScore {
   pub score_point: i32,
   pub achievement_rate: f64,
   pub accuracy: f64,
+  pub remaining_life: Option<u32>,
+  pub combo: u32,
+  pub max_combo: u32,
   correction_type_count: u32,
   wrong_type_count: u32,
   all_roman_len: usize,
@@ -32,23 +35,68 @@ impl GameScore {
       score_point,
       achievement_rate,
       accuracy,
+      remaining_life: None,
+      combo: 0,
+      max_combo: 0,
       correction_type_count: 0,
       wrong_type_count: 0,
       all_roman_len: 0,
     }
   }
 
+  // 複数の曲の成績を通しの成績にまとめる
+  pub fn merge(&self, other: &GameScore) -> Self {
+    let mut merged = Self {
+      score_point: self.score_point + other.score_point,
+      achievement_rate: 0.0,
+      accuracy: 0.0,
+      remaining_life: other.remaining_life,
+      combo: other.combo,
+      max_combo: self.max_combo.max(other.max_combo),
+      correction_type_count: self.correction_type_count
+        + other.correction_type_count,
+      wrong_type_count: self.wrong_type_count
+        + other.wrong_type_count,
+      all_roman_len: self.all_roman_len + other.all_roman_len,
+    };
+    merged.update_rates();
+    merged
+  }
+
+  // 途中で終わって遊ばなかった譜面の文字も、打てなかったものとして
+  // 達成率の分母に数える
+  pub fn merge_unplayed(&self, sections: &Sections) -> Self {
+    let mut merged = self.clone();
+    merged.all_roman_len += roman_len(sections);
+    merged.update_rates();
+    merged
+  }
+
+  pub fn correction_type_count(&self) -> u32 {
+    self.correction_type_count
+  }
+
+  pub fn wrong_type_count(&self) -> u32 {
+    self.wrong_type_count
+  }
+
   fn update(&mut self, type_result: &TypeResult) {
     match type_result {
       TypeResult::Mistaken => {
         self.wrong_type_count += 1;
+        self.combo = 0;
       }
       TypeResult::Succeed => {
         self.correction_type_count += 1;
+        self.combo += 1;
+        self.max_combo = self.max_combo.max(self.combo);
       }
       _ => return,
     };
+    self.update_rates();
+  }
 
+  fn update_rates(&mut self) {
     self.achievement_rate = (self.correction_type_count as f64
       / self.all_roman_len as f64)
       .min(1.);
@@ -61,6 +109,17 @@ impl GameScore {
   }
 }
 
+fn roman_len(sections: &Sections) -> usize {
+  sections.iter().fold(0, |acc, section| {
+    section.iter().fold(0, |acc, note| match note.content() {
+      NoteContent::Sentence { sentence, .. } => {
+        sentence.keys().will_input.chars().count() + acc
+      }
+      _ => acc,
+    }) + acc
+  })
+}
+
 pub struct GameActivity {
   state: State,
   sections: Sections,
@@ -69,34 +128,62 @@ pub struct GameActivity {
 
 impl GameActivity {
   pub fn new(sections: Sections) -> Self {
-    let all_roman_len = sections.iter().fold(0, |acc, section| {
-      section.iter().fold(0, |acc, note| match note.content() {
-        NoteContent::Sentence { sentence, .. } => {
-          sentence.roman().will_input.len() + acc
-        }
-        _ => acc,
-      }) + acc
-    });
-    let mut res = GameActivity {
+    let all_roman_len = roman_len(&sections);
+    GameActivity {
       state: State::BeforeStart,
       sections,
       score: GameScore {
         score_point: 0,
         achievement_rate: 0.0,
         accuracy: 0.0,
+        remaining_life: None,
+        combo: 0,
+        max_combo: 0,
         correction_type_count: 0,
         wrong_type_count: 0,
         all_roman_len,
       },
-    };
-    res.update_time(0.0.into());
-    res
+    }
+  }
+
+  // 始めるまでは時刻を進めても文は切り替わらない
+  pub fn start(&mut self) {
+    if let State::BeforeStart = self.state {
+      self.state = State::OnGame;
+    }
+  }
+
+  pub fn is_before_start(&self) -> bool {
+    matches!(self.state, State::BeforeStart)
+  }
+
+  // 始める前に見せておく最初の文
+  pub fn first_sentence(&self) -> Sentence {
+    self
+      .sections
+      .iter()
+      .flat_map(|section| section.iter())
+      .find_map(|note| match note.content() {
+        NoteContent::Sentence { sentence, .. } => {
+          Some(sentence.clone())
+        }
+        _ => None,
+      })
+      .unwrap_or_else(Sentence::empty)
   }
 
   pub fn current_section(&self) -> Option<&Section> {
     self.sections.current_section()
   }
 
+  pub fn section_stats(&self, index: usize) -> Option<SectionStats> {
+    self
+      .sections
+      .iter()
+      .nth(index)
+      .map(|section| section.stats())
+  }
+
   pub fn current_note(&self) -> Option<&Note> {
     self
       .sections
@@ -104,7 +191,27 @@ impl GameActivity {
       .map(|section| section.current_note())
   }
 
+  pub fn set_life(&mut self, life: Option<u32>) {
+    self.score.remaining_life = life;
+  }
+
+  pub fn damage(&mut self, amount: u32) {
+    if let Some(life) = self.score.remaining_life.as_mut() {
+      *life = life.saturating_sub(amount);
+      if *life == 0 {
+        self.state = State::GameOver;
+      }
+    }
+  }
+
   pub fn update_time(&mut self, time: Seconds) {
+    if let State::BeforeStart = self.state {
+      return;
+    }
+    if self.score.remaining_life == Some(0) {
+      self.state = State::GameOver;
+      return;
+    }
     self.state = State::OnGame;
     if self.sections.update(time).is_some() {
       return;
@@ -132,8 +239,8 @@ impl GameActivity {
     Sentence::empty()
   }
 
-  pub fn current_note_id(&self) -> NoteId {
-    self.current_section().map_or("".into(), |s| s.id())
+  pub fn current_note_id(&self) -> Option<NoteId> {
+    self.current_section().map(|s| s.id())
   }
 
   pub fn remaining_ratio(&self, time: Seconds) -> f64 {
@@ -143,6 +250,38 @@ impl GameActivity {
       .map_or(1.0, |note| note.duration().remaining_ratio(time))
   }
 
+  // 先の歌詞を見せるために、これから始まる文を開始時刻と合わせて返す
+  pub fn upcoming_sentences(
+    &self,
+    time: Seconds,
+    count: usize,
+  ) -> Vec<(Seconds, Sentence)> {
+    self
+      .sections
+      .upcoming_sentences(time, count)
+      .into_iter()
+      .filter_map(|note| match note.content() {
+        NoteContent::Sentence { sentence, .. } => {
+          Some((note.duration().start(), sentence.clone()))
+        }
+        _ => None,
+      })
+      .collect()
+  }
+
+  // 現在のノーツの開始時刻を起点に、それ以降のノーツの長さを伸縮させる
+  pub fn scale_upcoming(&mut self, ratio: f64) {
+    if let Some(origin) =
+      self.current_note().map(|note| note.duration().start())
+    {
+      self.sections.scale_after(origin, ratio);
+    }
+  }
+
+  pub fn break_combo(&mut self) {
+    self.score.combo = 0;
+  }
+
   pub fn point(&mut self, amount: i32) {
     self.score.score_point += amount;
   }
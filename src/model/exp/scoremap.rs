@@ -23,6 +23,15 @@ pub struct MusicInfo {
   pub song_author: String,
 }
 
+impl MusicInfo {
+  pub fn new(title: &str, song_author: &str) -> Self {
+    Self {
+      title: title.into(),
+      song_author: song_author.into(),
+    }
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct ScoremapMetadata(HashMap<String, String>);
 
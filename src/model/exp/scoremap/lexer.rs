@@ -2,6 +2,7 @@ use regex::Regex;
 use std::io::{BufReader, Read};
 
 use super::token::Token;
+use crate::model::exp::sentence::roman::RomanPreference;
 use pattern::{LexerCtx, TokenResult, Tokenizer};
 
 mod pattern;
@@ -31,18 +32,28 @@ pub enum ScoremapLexError {
   UnknownToken,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct ScoremapLoadConfig {
   ignore_unsupported_property: bool,
+  roman_preference: RomanPreference,
 }
 
 impl ScoremapLoadConfig {
   pub fn new() -> Self {
     ScoremapLoadConfig {
       ignore_unsupported_property: false,
+      roman_preference: RomanPreference::default(),
     }
   }
 
+  pub fn roman_preference(
+    mut self,
+    preference: RomanPreference,
+  ) -> Self {
+    self.roman_preference = preference;
+    self
+  }
+
   pub fn ignore_unsupported_property(
     mut self,
     whether: bool,
@@ -112,6 +123,9 @@ where
   let yomigana_reg = Regex::new(YOMIGANA)?;
   let yomigana = Lexer::new(yomigana_reg, yomigana_lexer);
 
+  let raw_text_reg = Regex::new(RAW_TEXT)?;
+  let raw_text = Lexer::new(raw_text_reg, raw_text_lexer);
+
   let caption_reg = Regex::new(CAPTION)?;
   let caption = Lexer::new(caption_reg, caption_lexer);
 
@@ -133,6 +147,7 @@ where
     .connect(caption)
     .connect(property)
     .connect(yomigana)
+    .connect(raw_text)
     .connect(section)
     .connect(lyrics);
 
@@ -15,6 +15,7 @@ const METADATA_KEYS: &[&str] = &[
   "score_author",
   "song_data",
   "bpm",
+  "offset",
 ];
 
 #[derive(Debug, Clone)]
@@ -109,17 +110,17 @@ pub fn command_lexer(
 pub const YOMIGANA: &str = r"^:([あいうえおかきくけこさしすせそたちつてとなにぬねのはひふへほまみむめもやゆよらりるれろわゐゑをんぁぃぅぇぉゃゅょゎっーがぎぐげござじずぜぞだぢづでどばびぶべぼぱぴぷぺぽ]+)$";
 pub fn yomigana_lexer(
   captures: Captures,
-  LexerCtx { line_num, .. }: &mut LexerCtx,
+  LexerCtx { line_num, cfg, .. }: &mut LexerCtx,
 ) -> TokenResult {
   let line_num = *line_num;
   let string = captures.get(1)?.as_str();
   let content = Yomigana({
-    let roman = RomanStr::new(string).map_err(|_e| {
-      InvalidStatementDefinition {
-        line_num,
-        reason: "ふりがなでのそのような平仮名の並びは未対応です。",
-      }
-    });
+    let roman =
+      RomanStr::with_preference(string, &cfg.roman_preference)
+        .map_err(|_e| InvalidStatementDefinition {
+          line_num,
+          reason: "ふりがなでのそのような平仮名の並びは未対応です。",
+        });
     if let Err(err) = roman {
       return Some(Err(err));
     }
@@ -128,6 +129,26 @@ pub fn yomigana_lexer(
   Some(Ok(Token { line_num, content }))
 }
 
+pub const RAW_TEXT: &str = r"^=(.+)$";
+pub fn raw_text_lexer(
+  captures: Captures,
+  LexerCtx { line_num, .. }: &mut LexerCtx,
+) -> TokenResult {
+  let line_num = *line_num;
+  let string = captures.get(1)?.as_str().trim_end();
+  if !string.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+    return Some(Err(InvalidStatementDefinition {
+      line_num,
+      reason:
+        "そのまま打つ文には英数字と記号と空白だけを使ってください。",
+    }));
+  }
+  Some(Ok(Token {
+    line_num,
+    content: RawText(string.to_owned()),
+  }))
+}
+
 pub const CAPTION: &str =
   r"^[[:space:]]*>>[[:space:]]*(.+)[[:space:]]*$";
 pub fn caption_lexer(
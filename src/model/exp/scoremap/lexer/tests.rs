@@ -197,6 +197,11 @@ fn pattern_tests() -> Result<(), super::ScoremapLexError> {
   assert!(reg.is_match(":はんばーがー"));
   assert!(reg.is_match(":ぅゎょぅじょっょぃ"));
 
+  let reg = Regex::new(RAW_TEXT)?;
+  assert!(reg.is_match("=Hello, world!"));
+  assert!(reg.is_match("=I'm 20 years old."));
+  assert!(!reg.is_match("="));
+
   let reg = Regex::new(CAPTION)?;
   assert!(reg.is_match(">>テスト"));
   assert!(reg.is_match(">>HAMBURGER"));
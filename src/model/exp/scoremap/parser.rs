@@ -101,6 +101,8 @@ pub fn parse(
 
   let yomigana_parser: Parser = Parser::new(yomigana_processor);
 
+  let raw_text_parser: Parser = Parser::new(raw_text_processor);
+
   let section_parser: Parser = Parser::new(section_processor);
 
   let lyrics_parser: Parser = Parser::new(lyrics_processor);
@@ -113,6 +115,7 @@ pub fn parse(
     .connect(caption_parser)
     .connect(property_parser)
     .connect(yomigana_parser)
+    .connect(raw_text_parser)
     .connect(section_parser)
     .connect(lyrics_parser)
     .connect(comment_parser);
@@ -5,7 +5,7 @@ use crate::model::exp::{
     token::{Token, TokenContent},
     ScoremapMetadata,
   },
-  sentence::Sentence,
+  sentence::{ruby::strip_ruby, Sentence},
   time::{Duration, MinuteSecond},
 };
 use std::collections::VecDeque;
@@ -233,8 +233,13 @@ pub(super) fn yomigana_processor(
       parsed_japanese, ..
     } = ctx;
     if let Some(lyrics) = parsed_japanese {
+      // 当て字のように読みが読み仮名に見つからないルビは、
+      // 取り除いて歌詞だけを表示する
       let sentence =
-        Sentence::from(lyrics.as_str(), yomigana.clone());
+        Sentence::with_ruby(lyrics.as_str(), yomigana.clone())
+          .unwrap_or_else(|| {
+            Sentence::from(&strip_ruby(lyrics).0, yomigana.clone())
+          });
       *parsed_japanese = None;
       return Some(Ok(Note::sentence(duration, sentence)));
     }
@@ -246,6 +251,31 @@ pub(super) fn yomigana_processor(
   None
 }
 
+// 直前に歌詞があればそれを表示し、なければ打つ文をそのまま表示する
+pub(super) fn raw_text_processor(
+  tokens: &mut VecDeque<&Token>,
+  ctx: &mut ParserCtx,
+) -> ParseResult {
+  if let Some(Token {
+    content: TokenContent::RawText(text),
+    line_num,
+  }) = tokens.front()
+  {
+    let duration = ctx.calc_duration(tokens, *line_num).ok()?;
+    tokens.remove(0);
+    // そのまま打つ文にはルビを付けない
+    let origin = ctx
+      .parsed_japanese
+      .take()
+      .map_or_else(|| text.clone(), |lyrics| strip_ruby(&lyrics).0);
+    return Some(Ok(Note::sentence(
+      duration,
+      Sentence::raw_text(&origin, text),
+    )));
+  }
+  None
+}
+
 pub(super) fn section_processor(
   tokens: &mut VecDeque<&Token>,
   ParserCtx {
@@ -1,6 +1,8 @@
-use crate::model::exp::time::Seconds;
+use crate::model::exp::time::{Duration, Seconds};
 use section::{
-  note::{Note, TypeResult},
+  note::{
+    sentence::InputMethod, Note, NoteContent, NoteId, TypeResult,
+  },
   Section,
 };
 
@@ -14,12 +16,17 @@ pub struct Sections {
 
 impl Sections {
   pub fn new(notes: Vec<Vec<Note>>) -> Self {
+    let chart = chart_hash(&notes);
     let sections: Vec<_> = notes
       .into_iter()
-      .map(|section| {
+      .enumerate()
+      .map(|(index, section)| {
         let first = section.first().unwrap().duration();
         let last = section.last().unwrap().duration();
-        Section::new(section.clone(), first.concat(last))
+        let mut section =
+          Section::new(section.clone(), first.concat(last));
+        section.assign_ids(chart, index);
+        section
       })
       .collect();
     Self {
@@ -28,6 +35,15 @@ impl Sections {
     }
   }
 
+  pub fn note_by_id(&self, id: &NoteId) -> Option<&Note> {
+    let note = self.sections.get(id.section)?.note(id.note)?;
+    if note.id() == *id {
+      Some(note)
+    } else {
+      None
+    }
+  }
+
   pub fn current_section(&self) -> Option<&Section> {
     self.sections.get(self.current_section_index)
   }
@@ -43,19 +59,58 @@ impl Sections {
     }
   }
 
+  // 毎フレーム呼ばれるので、今のセクションから外れたときだけ二分探索する
   pub fn update(&mut self, time: Seconds) -> Option<&Section> {
-    let mut index_opt = None;
-    for (index, section) in self.sections.iter_mut().enumerate() {
-      if section.update(&time) {
-        index_opt = Some(index);
-        break;
+    let index = match self.sections.get(self.current_section_index) {
+      Some(section) if section.duration().includes(&time) => {
+        self.current_section_index
       }
+      _ => self
+        .sections
+        .partition_point(|section| section.duration().end() <= time),
+    };
+    let section = self.sections.get_mut(index)?;
+    if !section.update(&time) {
+      return None;
     }
-    if let Some(index) = index_opt {
-      self.current_section_index = index;
-      self.current_section()
-    } else {
-      None
+    self.current_section_index = index;
+    self.current_section()
+  }
+
+  // time より後に始まる文のノーツを、始まる順に count 個まで返す
+  pub fn upcoming_sentences(
+    &self,
+    time: Seconds,
+    count: usize,
+  ) -> Vec<&Note> {
+    self
+      .sections
+      .iter()
+      .skip(self.current_section_index)
+      .flat_map(|section| section.iter())
+      .filter(|note| time < note.duration().start())
+      .filter(|note| {
+        matches!(note.content(), NoteContent::Sentence { .. })
+      })
+      .take(count)
+      .collect()
+  }
+
+  pub fn scale_after(&mut self, origin: Seconds, ratio: f64) {
+    for section in self.sections.iter_mut() {
+      section.scale_after(origin, ratio);
+    }
+  }
+
+  pub fn shift(&mut self, amount: Seconds) {
+    for section in self.sections.iter_mut() {
+      section.shift(amount);
+    }
+  }
+
+  pub fn set_input_method(&mut self, method: InputMethod) {
+    for section in self.sections.iter_mut() {
+      section.set_input_method(method);
     }
   }
 
@@ -68,3 +123,48 @@ impl Sections {
     self.sections.len()
   }
 }
+
+// 読み込むたびに変わらないよう、乱数を使わない FNV-1a で譜面の内容を要約する
+fn chart_hash(notes: &[Vec<Note>]) -> u64 {
+  const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+  const PRIME: u64 = 0x100000001b3;
+
+  let mut hash = OFFSET_BASIS;
+  let mut write = |bytes: &[u8]| {
+    for &byte in bytes {
+      hash ^= byte as u64;
+      hash = hash.wrapping_mul(PRIME);
+    }
+  };
+  let write_duration = |write: &mut dyn FnMut(&[u8]),
+                        duration: &Duration| {
+    for time in [duration.start(), duration.end()] {
+      write(&((time.as_f64() * 1e3).round() as i64).to_le_bytes());
+    }
+  };
+  for section in notes {
+    write(b"[");
+    for note in section {
+      write_duration(&mut write, note.duration());
+      match note.content() {
+        NoteContent::Sentence { sentence, .. } => {
+          write(b"s");
+          write(sentence.origin().as_bytes());
+          write(b"\0");
+          // ローマ字の綴りは設定で変わるので、読み仮名で要約する
+          write(sentence.yomiagana().will_input.as_bytes());
+          if sentence.input_method() == InputMethod::Text {
+            write(sentence.keys().will_input.as_bytes());
+          }
+        }
+        NoteContent::Caption(caption) => {
+          write(b"c");
+          write(caption.as_bytes());
+        }
+        NoteContent::Blank => write(b"b"),
+      }
+      write(b"\0");
+    }
+  }
+  hash
+}
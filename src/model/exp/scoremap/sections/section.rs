@@ -1,8 +1,18 @@
 use crate::model::exp::time::{Duration, Seconds};
-use note::{Note, NoteId, TypeResult};
+use note::{
+  sentence::InputMethod, Note, NoteContent, NoteId, TypeResult,
+};
 
 pub mod note;
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionStats {
+  pub index: usize,
+  pub accuracy: f64,
+  pub completed_sentences: usize,
+  pub sentences: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct Section {
   notes: Vec<Note>,
@@ -19,6 +29,20 @@ impl Section {
     }
   }
 
+  pub fn assign_ids(&mut self, chart: u64, section: usize) {
+    for (note, entry) in self.notes.iter_mut().enumerate() {
+      entry.set_id(NoteId {
+        chart,
+        section,
+        note,
+      });
+    }
+  }
+
+  pub fn note(&self, index: usize) -> Option<&Note> {
+    self.notes.get(index)
+  }
+
   pub fn current_note(&self) -> &Note {
     &self.notes[self.current_note_index]
   }
@@ -36,7 +60,7 @@ impl Section {
       self.notes.iter().map(|note| note.accuracy()).collect();
     accuracies.sort_by(|ref a, ref b| a.partial_cmp(b).unwrap());
 
-    if accuracies.len() % 2 == 0 {
+    if accuracies.len() % 2 == 1 {
       accuracies[accuracies.len() / 2]
     } else {
       (accuracies[accuracies.len() / 2 - 1]
@@ -45,14 +69,65 @@ impl Section {
     }
   }
 
+  pub fn stats(&self) -> SectionStats {
+    let sentences: Vec<_> = self
+      .notes
+      .iter()
+      .filter_map(|note| match note.content() {
+        NoteContent::Sentence { sentence, .. } => Some(sentence),
+        _ => None,
+      })
+      .collect();
+    SectionStats {
+      index: self.id().section,
+      accuracy: self.accuracy(),
+      completed_sentences: sentences
+        .iter()
+        .filter(|sentence| sentence.completed())
+        .count(),
+      sentences: sentences.len(),
+    }
+  }
+
+  pub fn duration(&self) -> &Duration {
+    &self.duration
+  }
+
+  // ノーツは時間順に隙間なく並んでいるので、二分探索で探す
   pub fn update(&mut self, time: &Seconds) -> bool {
-    for (index, note) in self.notes.iter().enumerate() {
-      if note.duration().includes(time) {
+    if self.current_note().duration().includes(time) {
+      return true;
+    }
+    let index = self
+      .notes
+      .partition_point(|note| note.duration().end() <= *time);
+    match self.notes.get(index) {
+      Some(note) if note.duration().includes(time) => {
         self.current_note_index = index;
-        return true;
+        true
       }
+      _ => false,
+    }
+  }
+
+  pub fn scale_after(&mut self, origin: Seconds, ratio: f64) {
+    for note in self.notes.iter_mut() {
+      note.scale_duration(origin, ratio);
+    }
+    self.duration = self.duration.scale_from(origin, ratio);
+  }
+
+  pub fn shift(&mut self, amount: Seconds) {
+    for note in self.notes.iter_mut() {
+      note.shift_duration(amount);
+    }
+    self.duration = self.duration.shift(amount);
+  }
+
+  pub fn set_input_method(&mut self, method: InputMethod) {
+    for note in self.notes.iter_mut() {
+      note.set_input_method(method);
     }
-    false
   }
 
   pub fn iter(&self) -> impl Iterator<Item = &Note> {
@@ -64,3 +139,31 @@ impl Section {
     self.notes.len()
   }
 }
+
+#[test]
+fn median_accuracy() {
+  use note::sentence::Sentence;
+
+  let duration = || Duration::new(0.0, 1.0).unwrap();
+  let unplayed =
+    || Note::sentence(duration(), Sentence::new("詩", "し").unwrap());
+
+  // 奇数個なら真ん中、偶数個なら真ん中の 2 つの平均をとる
+  let single =
+    Section::new(vec![Note::blank(duration())], duration());
+  assert_eq!(1.0, single.accuracy());
+  let odd = Section::new(
+    vec![
+      unplayed(),
+      Note::blank(duration()),
+      Note::blank(duration()),
+    ],
+    duration(),
+  );
+  assert_eq!(1.0, odd.accuracy());
+  let even = Section::new(
+    vec![unplayed(), Note::blank(duration())],
+    duration(),
+  );
+  assert_eq!(0.5, even.accuracy());
+}
@@ -1,5 +1,6 @@
 use scoring::Scoring;
-use sentence::Sentence;
+use sentence::{InputMethod, Sentence};
+use std::fmt::{Display, Formatter};
 
 mod scoring;
 pub mod sentence;
@@ -18,7 +19,20 @@ pub enum NoteContent {
   Blank,
 }
 
-pub type NoteId = String;
+// 譜面の内容のハッシュと、何番目のセクションの何番目のノーツかで決まる。
+// 同じ譜面なら読み込むたびに同じ値になる
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NoteId {
+  pub chart: u64,
+  pub section: usize,
+  pub note: usize,
+}
+
+impl Display for NoteId {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:016x}-{}-{}", self.chart, self.section, self.note)
+  }
+}
 
 #[derive(Debug, Clone)]
 pub struct Note {
@@ -28,19 +42,13 @@ pub struct Note {
   scoring: Scoring,
 }
 
-use crate::model::exp::time::Duration;
-use rand::distributions::Alphanumeric;
-use rand::{thread_rng, Rng};
+use crate::model::exp::time::{Duration, Seconds};
 
 impl Note {
   fn new(duration: Duration, content: NoteContent) -> Self {
-    let id = thread_rng()
-      .sample_iter(&Alphanumeric)
-      .take(5)
-      .map(|n| n as char)
-      .collect();
+    // 譜面に並べるときに Sections が振り直す
     Self {
-      id,
+      id: NoteId::default(),
       duration,
       content,
       scoring: Scoring::new(),
@@ -66,13 +74,32 @@ impl Note {
   }
 
   pub fn id(&self) -> NoteId {
-    self.id.clone()
+    self.id
+  }
+
+  pub fn set_id(&mut self, id: NoteId) {
+    self.id = id;
   }
 
   pub fn duration(&self) -> &Duration {
     &self.duration
   }
 
+  pub fn scale_duration(&mut self, origin: Seconds, ratio: f64) {
+    self.duration = self.duration.scale_from(origin, ratio);
+  }
+
+  pub fn shift_duration(&mut self, amount: Seconds) {
+    self.duration = self.duration.shift(amount);
+  }
+
+  pub fn set_input_method(&mut self, method: InputMethod) {
+    if let NoteContent::Sentence { sentence, .. } = &mut self.content
+    {
+      sentence.set_input_method(method);
+    }
+  }
+
   pub fn input(&mut self, typed: char) -> TypeResult {
     use NoteContent::Sentence;
     use TypeResult::*;
@@ -1,7 +1,19 @@
+use kana::KanaStr;
 use roman::{RomanParseError, RomanStr};
+use ruby::{align_ruby, ruby_pieces, strip_ruby, Ruby, RubyPiece};
 use std::fmt::{Debug, Formatter};
 
+pub mod kana;
 pub mod roman;
+pub mod ruby;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputMethod {
+  Roman,
+  Kana,
+  // 歌詞の文字をそのまま打つ。譜面で指定され、入力方式の設定では変わらない
+  Text,
+}
 
 pub struct TypingStr {
   pub will_input: String,
@@ -12,6 +24,9 @@ pub struct TypingStr {
 pub struct Sentence {
   origin: String,
   hiragana: RomanStr,
+  kana: KanaStr,
+  method: InputMethod,
+  ruby: Vec<Ruby>,
 }
 
 impl Debug for Sentence {
@@ -26,10 +41,7 @@ impl Sentence {
     origin: &str,
     to_input: &str,
   ) -> Result<Self, RomanParseError> {
-    Ok(Sentence {
-      origin: origin.into(),
-      hiragana: RomanStr::new(to_input)?,
-    })
+    Ok(Self::from(origin, RomanStr::new(to_input)?))
   }
 
   #[allow(dead_code)]
@@ -42,34 +54,78 @@ impl Sentence {
     for inputted in inputted.chars() {
       roman_str.input(inputted);
     }
-    Ok(Sentence {
-      origin: origin.into(),
-      hiragana: roman_str,
-    })
+    Ok(Self::from(origin, roman_str))
   }
 
   pub fn empty() -> Self {
-    Sentence {
-      origin: "".into(),
-      hiragana: RomanStr::new("").unwrap(),
-    }
+    Self::from("", RomanStr::new("").unwrap())
   }
 
   pub fn from(origin: &str, yomigana: RomanStr) -> Self {
     Sentence {
       origin: origin.into(),
+      kana: KanaStr::new(yomigana.yomigana()),
       hiragana: yomigana,
+      method: InputMethod::Roman,
+      ruby: vec![],
     }
   }
 
+  // 歌詞に書いたルビを取り除いて、読み仮名と対応付ける。
+  // ルビの読みが読み仮名に見つからなければ None を返す
+  pub fn with_ruby(lyrics: &str, yomigana: RomanStr) -> Option<Self> {
+    let (origin, spans) = strip_ruby(lyrics);
+    let ruby = align_ruby(spans, yomigana.yomigana())?;
+    Some(Sentence {
+      ruby,
+      ..Self::from(&origin, yomigana)
+    })
+  }
+
+  pub fn raw_text(origin: &str, text: &str) -> Self {
+    Sentence {
+      origin: origin.into(),
+      hiragana: RomanStr::literal(text),
+      kana: KanaStr::new(""),
+      method: InputMethod::Text,
+      ruby: vec![],
+    }
+  }
+
+  pub fn set_input_method(&mut self, method: InputMethod) {
+    if self.method != InputMethod::Text {
+      self.method = method;
+    }
+  }
+
+  pub fn input_method(&self) -> InputMethod {
+    self.method
+  }
+
   pub fn origin(&self) -> &str {
     self.origin.as_str()
   }
 
+  // ルビを付けた親文字ごとに分けた歌詞
+  pub fn ruby_pieces(&self) -> Vec<RubyPiece> {
+    let inputted = self.yomiagana().inputted.chars().count();
+    ruby_pieces(&self.origin, &self.ruby, inputted)
+  }
+
   pub fn yomiagana(&self) -> TypingStr {
-    TypingStr {
-      will_input: self.hiragana.will_input_yomigana().collect(),
-      inputted: self.hiragana.inputted_yomigana().collect(),
+    match self.method {
+      InputMethod::Roman => TypingStr {
+        will_input: self.hiragana.will_input_yomigana().collect(),
+        inputted: self.hiragana.inputted_yomigana().collect(),
+      },
+      InputMethod::Kana => TypingStr {
+        will_input: self.kana.will_input_yomigana().collect(),
+        inputted: self.kana.inputted_yomigana().collect(),
+      },
+      InputMethod::Text => TypingStr {
+        will_input: "".into(),
+        inputted: "".into(),
+      },
     }
   }
 
@@ -80,11 +136,114 @@ impl Sentence {
     }
   }
 
+  // ローマ字で打つときの、いま打っている仮名と綴りの候補
+  pub fn alternatives(&self) -> Option<(String, Vec<String>)> {
+    match self.method {
+      InputMethod::Roman => self.hiragana.alternatives(),
+      InputMethod::Kana | InputMethod::Text => None,
+    }
+  }
+
+  // 次に打てるキー。ローマ字ではどの綴りで続けてもよいので複数になる
+  pub fn next_keys(&self) -> Vec<char> {
+    match self.method {
+      InputMethod::Roman => self.hiragana.next_keys(),
+      InputMethod::Kana | InputMethod::Text => self
+        .keys()
+        .will_input
+        .chars()
+        .next()
+        .map_or(vec![], |key| vec![key]),
+    }
+  }
+
+  // 入力方式に応じて、打つキーの並びを返す
+  pub fn keys(&self) -> TypingStr {
+    match self.method {
+      InputMethod::Roman | InputMethod::Text => self.roman(),
+      InputMethod::Kana => TypingStr {
+        will_input: self.kana.will_input_keys(),
+        inputted: self.kana.inputted_keys(),
+      },
+    }
+  }
+
   pub fn input(&mut self, typed: char) -> bool {
-    self.hiragana.input(typed)
+    match self.method {
+      InputMethod::Roman | InputMethod::Text => {
+        self.hiragana.input(typed)
+      }
+      InputMethod::Kana => self.kana.input(typed),
+    }
   }
 
   pub fn completed(&self) -> bool {
-    self.hiragana.completed()
+    match self.method {
+      InputMethod::Roman | InputMethod::Text => {
+        self.hiragana.completed()
+      }
+      InputMethod::Kana => self.kana.completed(),
+    }
+  }
+}
+
+#[test]
+fn kana_input() -> Result<(), RomanParseError> {
+  let mut sentence = Sentence::new("打鍵", "だけん")?;
+  sentence.set_input_method(InputMethod::Kana);
+  assert_eq!("た゛けん", sentence.keys().will_input);
+  assert!(!sentence.input('d'));
+  assert!(sentence.input('た'));
+  assert!(sentence.input('゛'));
+  assert_eq!("けん", sentence.yomiagana().will_input);
+  assert!(sentence.input('け'));
+  assert!(sentence.input('ん'));
+  assert!(sentence.completed());
+  Ok(())
+}
+
+#[test]
+fn ruby_input() -> Result<(), RomanParseError> {
+  let mut sentence = Sentence::with_ruby(
+    "千本桜(せんぼんざくら)",
+    RomanStr::new("せんぼんざくら")?,
+  )
+  .unwrap();
+  assert_eq!("千本桜", sentence.origin());
+  for typed in "senbonzakur".chars() {
+    assert!(sentence.input(typed));
+  }
+  assert!(!sentence.ruby_pieces()[0].typed);
+  assert!(sentence.input('a'));
+  assert!(sentence.ruby_pieces()[0].typed);
+  assert!(Sentence::with_ruby("桜(さくら)", RomanStr::new("はな")?)
+    .is_none());
+  Ok(())
+}
+
+#[test]
+fn alternatives() -> Result<(), RomanParseError> {
+  let mut sentence = Sentence::new("詩", "し")?;
+  let (kana, styles) = sentence.alternatives().unwrap();
+  assert_eq!("し", kana);
+  assert_eq!(vec!["si", "shi"], styles);
+  assert_eq!(vec!['s'], sentence.next_keys());
+  assert!(sentence.input('s'));
+  assert_eq!(vec!['i', 'h'], sentence.next_keys());
+  sentence.set_input_method(InputMethod::Kana);
+  assert_eq!(None, sentence.alternatives());
+  Ok(())
+}
+
+#[test]
+fn raw_text_input() {
+  let mut sentence = Sentence::raw_text("Let it go", "Let it go");
+  sentence.set_input_method(InputMethod::Kana);
+  assert_eq!(InputMethod::Text, sentence.input_method());
+  assert_eq!("Let it go", sentence.keys().will_input);
+  assert!(!sentence.input('l'));
+  for typed in "Let it go".chars() {
+    assert!(sentence.input(typed));
   }
+  assert!(sentence.completed());
 }
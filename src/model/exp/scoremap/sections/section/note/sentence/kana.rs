@@ -0,0 +1,131 @@
+use std::fmt::{Debug, Formatter};
+
+pub const DAKUTEN: char = '゛';
+pub const HANDAKUTEN: char = '゜';
+
+// 仮名入力で打つキーの並びに分解する。濁音と半濁音は清音と濁点・半濁点の 2 打になる
+fn decompose(kana: char) -> Vec<char> {
+  const VOICED: &[(&str, &str)] = &[
+    (
+      "がぎぐげござじずぜぞだぢづでどばびぶべぼ",
+      "かきくけこさしすせそたちつてとはひふへほ",
+    ),
+    ("ゔ", "う"),
+  ];
+  const SEMI_VOICED: (&str, &str) = ("ぱぴぷぺぽ", "はひふへほ");
+
+  for (voiced, plain) in VOICED {
+    if let Some(index) = voiced.chars().position(|c| c == kana) {
+      return vec![plain.chars().nth(index).unwrap(), DAKUTEN];
+    }
+  }
+  let (semi_voiced, plain) = SEMI_VOICED;
+  if let Some(index) = semi_voiced.chars().position(|c| c == kana) {
+    return vec![plain.chars().nth(index).unwrap(), HANDAKUTEN];
+  }
+  vec![kana]
+}
+
+#[derive(Clone, PartialEq)]
+pub struct KanaStr {
+  yomigana: Vec<char>,
+  // 打つキーと、それがどの仮名のものか
+  keys: Vec<(char, usize)>,
+  inputted: usize,
+}
+
+impl Debug for KanaStr {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", self.keys.iter().map(|&(key, _)| key))
+  }
+}
+
+impl KanaStr {
+  pub fn new(yomigana: &str) -> Self {
+    let yomigana: Vec<char> = yomigana.chars().collect();
+    let keys = yomigana
+      .iter()
+      .enumerate()
+      .flat_map(|(index, &kana)| {
+        decompose(kana).into_iter().map(move |key| (key, index))
+      })
+      .collect();
+    Self {
+      yomigana,
+      keys,
+      inputted: 0,
+    }
+  }
+
+  fn inputted_yomigana_index(&self) -> usize {
+    self
+      .keys
+      .get(self.inputted)
+      .map_or(self.yomigana.len(), |&(_, index)| index)
+  }
+
+  pub fn will_input_yomigana(
+    &self,
+  ) -> impl Iterator<Item = char> + '_ {
+    self.yomigana[self.inputted_yomigana_index()..]
+      .iter()
+      .cloned()
+  }
+
+  pub fn inputted_yomigana(&self) -> impl Iterator<Item = char> + '_ {
+    self.yomigana[..self.inputted_yomigana_index()]
+      .iter()
+      .cloned()
+  }
+
+  pub fn will_input_keys(&self) -> String {
+    self.keys[self.inputted..]
+      .iter()
+      .map(|&(key, _)| key)
+      .collect()
+  }
+
+  pub fn inputted_keys(&self) -> String {
+    self.keys[..self.inputted]
+      .iter()
+      .map(|&(key, _)| key)
+      .collect()
+  }
+
+  pub fn input(&mut self, typed: char) -> bool {
+    if self.keys.get(self.inputted).map(|&(key, _)| key)
+      == Some(typed)
+    {
+      self.inputted += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  pub fn completed(&self) -> bool {
+    self.keys.len() <= self.inputted
+  }
+}
+
+#[test]
+fn voiced() {
+  let mut kana = KanaStr::new("ぎたーぽっぷ");
+  assert_eq!("き゛たーほ゜っふ゜", kana.will_input_keys());
+  assert!(kana.input('き'));
+  assert_eq!(
+    "ぎたーぽっぷ",
+    kana.will_input_yomigana().collect::<String>()
+  );
+  assert!(!kana.input('た'));
+  assert!(kana.input(DAKUTEN));
+  assert_eq!("ぎ", kana.inputted_yomigana().collect::<String>());
+  assert_eq!("き゛", kana.inputted_keys());
+  for key in "たーほ゜っふ".chars() {
+    assert!(kana.input(key));
+  }
+  assert!(!kana.completed());
+  assert!(kana.input(HANDAKUTEN));
+  assert!(kana.completed());
+  assert_eq!("", kana.will_input_yomigana().collect::<String>());
+}
@@ -1,7 +1,11 @@
+mod input_scheme;
 mod roman_char;
 mod roman_lexer;
+mod roman_preference;
 mod roman_str;
 
+pub use self::input_scheme::*;
 pub use self::roman_char::*;
 pub use self::roman_lexer::*;
+pub use self::roman_preference::*;
 pub use self::roman_str::*;
@@ -0,0 +1,182 @@
+use super::RomanPreferenceError;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputScheme {
+  Standard,
+  Azik,
+  // Dvorak 配列向けに、AZIK の拡張を左手の段の上下のキーに置いたもの
+  Act,
+}
+
+const AZIK_ROWS: &[(&str, [Option<char>; 5])] = &[
+  (
+    "k",
+    [Some('か'), Some('き'), Some('く'), Some('け'), Some('こ')],
+  ),
+  (
+    "s",
+    [Some('さ'), Some('し'), Some('す'), Some('せ'), Some('そ')],
+  ),
+  (
+    "t",
+    [Some('た'), Some('ち'), Some('つ'), Some('て'), Some('と')],
+  ),
+  (
+    "n",
+    [Some('な'), Some('に'), Some('ぬ'), Some('ね'), Some('の')],
+  ),
+  (
+    "h",
+    [Some('は'), Some('ひ'), Some('ふ'), Some('へ'), Some('ほ')],
+  ),
+  (
+    "m",
+    [Some('ま'), Some('み'), Some('む'), Some('め'), Some('も')],
+  ),
+  ("y", [Some('や'), None, Some('ゆ'), None, Some('よ')]),
+  (
+    "r",
+    [Some('ら'), Some('り'), Some('る'), Some('れ'), Some('ろ')],
+  ),
+  ("w", [Some('わ'), None, None, None, None]),
+  (
+    "g",
+    [Some('が'), Some('ぎ'), Some('ぐ'), Some('げ'), Some('ご')],
+  ),
+  (
+    "z",
+    [Some('ざ'), Some('じ'), Some('ず'), Some('ぜ'), Some('ぞ')],
+  ),
+  (
+    "d",
+    [Some('だ'), Some('ぢ'), Some('づ'), Some('で'), Some('ど')],
+  ),
+  (
+    "b",
+    [Some('ば'), Some('び'), Some('ぶ'), Some('べ'), Some('ぼ')],
+  ),
+  (
+    "p",
+    [Some('ぱ'), Some('ぴ'), Some('ぷ'), Some('ぺ'), Some('ぽ')],
+  ),
+];
+
+// 各行の子音に続けて、撥音と二重母音をひと打ちで打つキー
+struct Extension {
+  // か行の子音。ACT では右手で打てる c にする
+  k_row: &'static str,
+  // 段ごとに、撥音 (ん) を続けるときのキー
+  nasal_keys: [char; 5],
+  // あい・うう・えい・おう を打つときのキー
+  diphthong_keys: [char; 4],
+}
+
+const AZIK: Extension = Extension {
+  k_row: "k",
+  nasal_keys: ['z', 'k', 'j', 'd', 'l'],
+  diphthong_keys: ['q', 'h', 'w', 'p'],
+};
+
+// Dvorak 配列で、撥音は母音のキーの下の段、二重母音は上の段にある
+const ACT: Extension = Extension {
+  k_row: "c",
+  nasal_keys: [';', 'x', 'k', 'j', 'q'],
+  diphthong_keys: ['\'', 'p', '.', ','],
+};
+
+impl InputScheme {
+  // 仮名の並びをひと打ちにまとめる綴りを返す
+  pub fn styles(&self, kana: &str) -> Vec<String> {
+    match self {
+      InputScheme::Standard => vec![],
+      InputScheme::Azik => azik_styles(kana),
+      InputScheme::Act => extended_styles(kana, &ACT),
+    }
+  }
+
+  pub fn max_kana_len(&self) -> usize {
+    match self {
+      InputScheme::Standard => 0,
+      InputScheme::Azik | InputScheme::Act => 2,
+    }
+  }
+}
+
+fn azik_styles(kana: &str) -> Vec<String> {
+  match kana {
+    "ん" => vec!["q".into()],
+    "っ" => vec![";".into()],
+    _ => extended_styles(kana, &AZIK),
+  }
+}
+
+fn extended_styles(kana: &str, extension: &Extension) -> Vec<String> {
+  let chars: Vec<char> = kana.chars().collect();
+  let (head, tail) = match chars.as_slice() {
+    &[head, tail] => (head, tail),
+    _ => return vec![],
+  };
+  let found = AZIK_ROWS.iter().find_map(|(consonant, row)| {
+    row
+      .iter()
+      .position(|&c| c == Some(head))
+      .map(|vowel| (*consonant, vowel))
+  });
+  let (consonant, vowel) = match found {
+    Some(("k", vowel)) => (extension.k_row, vowel),
+    Some(found) => found,
+    None => return vec![],
+  };
+  let [ai, uu, ei, ou] = extension.diphthong_keys;
+  let key = match (vowel, tail) {
+    (_, 'ん') => extension.nasal_keys[vowel],
+    (0, 'い') => ai,
+    (2, 'う') => uu,
+    (3, 'い') => ei,
+    (4, 'う') => ou,
+    _ => return vec![],
+  };
+  vec![format!("{}{}", consonant, key)]
+}
+
+impl FromStr for InputScheme {
+  type Err = RomanPreferenceError;
+
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    match name {
+      "standard" => Ok(InputScheme::Standard),
+      "azik" => Ok(InputScheme::Azik),
+      "act" => Ok(InputScheme::Act),
+      _ => Err(RomanPreferenceError::UnknownScheme(name.into())),
+    }
+  }
+}
+
+#[test]
+fn azik() {
+  let azik = InputScheme::Azik;
+  assert_eq!(vec!["kz"], azik.styles("かん"));
+  assert_eq!(vec!["sk"], azik.styles("しん"));
+  assert_eq!(vec!["tq"], azik.styles("たい"));
+  assert_eq!(vec!["kp"], azik.styles("こう"));
+  assert_eq!(vec!["yh"], azik.styles("ゆう"));
+  assert_eq!(vec!["q"], azik.styles("ん"));
+  assert_eq!(vec!["kq"], azik.styles("かい"));
+  assert!(azik.styles("きい").is_empty());
+  assert!(InputScheme::Standard.styles("かん").is_empty());
+}
+
+#[test]
+fn act() {
+  let act = InputScheme::Act;
+  assert_eq!(vec!["c;"], act.styles("かん"));
+  assert_eq!(vec!["sx"], act.styles("しん"));
+  assert_eq!(vec!["t'"], act.styles("たい"));
+  assert_eq!(vec!["c,"], act.styles("こう"));
+  assert_eq!(vec!["yp"], act.styles("ゆう"));
+  assert_eq!(vec!["nq"], act.styles("のん"));
+  assert_eq!(vec!["m."], act.styles("めい"));
+  assert!(act.styles("ん").is_empty());
+  assert!(matches!("act".parse(), Ok(InputScheme::Act)));
+}
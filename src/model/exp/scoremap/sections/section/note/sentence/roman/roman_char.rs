@@ -1,10 +1,14 @@
-use std::fmt::{Debug, Formatter, Result};
+use std::{
+  borrow::Cow,
+  fmt::{Debug, Formatter, Result},
+};
 
 #[derive(Clone, PartialEq)]
 pub struct RomanChar {
-  styles: Vec<&'static str>,
-  determined_style: Option<&'static str>,
+  styles: Vec<Cow<'static, str>>,
+  determined_style: Option<Cow<'static, str>>,
   inputted: String,
+  kana_len: usize,
 }
 
 impl Debug for RomanChar {
@@ -16,31 +20,110 @@ impl Debug for RomanChar {
 impl RomanChar {
   pub fn new(styles: &[&'static str]) -> Self {
     Self {
-      styles: Vec::from(styles),
+      styles: styles.iter().map(|&s| s.into()).collect(),
       determined_style: None,
       inputted: String::new(),
+      kana_len: 1,
     }
   }
 
-  fn determine(&mut self, input: &str) -> Option<&'static str> {
-    let filtered: Vec<_> = self
+  // 変換せずにその文字をそのまま打つ
+  pub fn literal(c: char) -> Self {
+    Self {
+      styles: vec![c.to_string().into()],
+      determined_style: None,
+      inputted: String::new(),
+      kana_len: 1,
+    }
+  }
+
+  // 続けて並んだ文字を、それぞれの綴りを繋げたものを受け付けるひとまとまりにする
+  pub fn concat(chars: &[RomanChar]) -> Self {
+    let mut styles: Vec<Cow<'static, str>> = vec!["".into()];
+    for roman_char in chars {
+      styles = styles
+        .iter()
+        .flat_map(|head| {
+          roman_char
+            .styles
+            .iter()
+            .map(move |tail| [head.as_ref(), tail].concat().into())
+        })
+        .collect();
+    }
+    Self {
+      styles,
+      determined_style: None,
+      inputted: String::new(),
+      kana_len: chars.iter().map(|c| c.kana_len).sum(),
+    }
+  }
+
+  fn determine(&mut self, input: &str) -> Option<Cow<'static, str>> {
+    self
       .styles
       .iter()
-      .filter(|style| style.starts_with(input))
-      .collect();
-    if filtered.is_empty() {
-      None
+      .find(|style| style.starts_with(input))
+      .cloned()
+  }
+
+  pub fn styles(&self) -> &[Cow<'static, str>] {
+    &self.styles
+  }
+
+  // ここまでの入力に続けて打てる綴り
+  pub fn acceptable_styles(&self) -> Vec<&str> {
+    self
+      .styles
+      .iter()
+      .filter(|style| style.starts_with(self.inputted.as_str()))
+      .map(|style| style.as_ref())
+      .collect()
+  }
+
+  // 次に打てるキー。綴りの順に、重なりを除いて並べる
+  pub fn next_keys(&self) -> Vec<char> {
+    let mut keys = vec![];
+    for style in self.acceptable_styles() {
+      if let Some(key) = style[self.inputted.len()..].chars().next() {
+        if !keys.contains(&key) {
+          keys.push(key);
+        }
+      }
+    }
+    keys
+  }
+
+  pub fn kana_len(&self) -> usize {
+    self.kana_len
+  }
+
+  pub fn set_kana_len(&mut self, kana_len: usize) {
+    self.kana_len = kana_len;
+  }
+
+  pub fn prefer(&mut self, style: &str) -> bool {
+    if let Some(index) = self.styles.iter().position(|s| s == style) {
+      let preferred = self.styles.remove(index);
+      self.styles.insert(0, preferred);
+      true
     } else {
-      Some(filtered[0])
+      false
     }
   }
 
-  pub fn styles(&self) -> &[&str] {
-    &self.styles
+  // 元の綴りにない入力方式の綴りも受け付けるようにして、それを優先する
+  pub fn prefer_or_add(&mut self, style: &str) {
+    if !self.prefer(style) {
+      self.styles.insert(0, style.to_owned().into());
+    }
   }
 
   pub fn determined_style(&self) -> &str {
-    self.determined_style.unwrap_or_else(|| self.styles()[0])
+    self
+      .determined_style
+      .as_deref()
+      .unwrap_or_else(|| &self.styles[0])
   }
 
   pub fn input(&mut self, typed: char) -> bool {
@@ -63,8 +146,8 @@ impl RomanChar {
     let fixed: Vec<_> = self
       .styles
       .iter()
-      .cloned()
       .filter(|s| s.starts_with(typed))
+      .cloned()
       .collect();
     if !fixed.is_empty() {
       self.styles = fixed;
@@ -86,3 +169,28 @@ fn tea() {
   assert!(tea.input('t'));
   assert_eq!("tya", tea.determined_style());
 }
+
+#[test]
+fn alternatives() {
+  let mut shi = RomanChar::new(&["si", "shi", "ci"]);
+  assert_eq!(vec!["si", "shi", "ci"], shi.acceptable_styles());
+  assert_eq!(vec!['s', 'c'], shi.next_keys());
+  assert!(shi.input('s'));
+  assert_eq!(vec!["si", "shi"], shi.acceptable_styles());
+  assert_eq!(vec!['i', 'h'], shi.next_keys());
+}
+
+#[test]
+fn concat() {
+  let mut can = RomanChar::concat(&[
+    RomanChar::new(&["ka", "ca"]),
+    RomanChar::new(&["nn", "xn"]),
+  ]);
+  assert_eq!(2, can.kana_len());
+  assert_eq!(&["kann", "kaxn", "cann", "caxn"], can.styles());
+  can.prefer_or_add("kz");
+  assert_eq!("kz", can.determined_style());
+  assert!(can.input('k'));
+  assert!(can.input('a'));
+  assert_eq!("kann", can.determined_style());
+}
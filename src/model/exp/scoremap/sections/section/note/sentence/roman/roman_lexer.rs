@@ -1,4 +1,4 @@
-use super::RomanChar;
+use super::{RomanChar, RomanPreference};
 use crate::model::exp::scoremap::{
   lexer::ScoremapLexError, ScoremapError,
 };
@@ -20,8 +20,70 @@ impl From<RomanParseError> for ScoremapError {
 }
 
 pub fn parse(
+  romans: &mut Vec<RomanChar>,
+  yomigana: &[char],
+) -> Result<(), RomanParseError> {
+  parse_with_preference(romans, yomigana, &RomanPreference::default())
+}
+
+pub fn parse_with_preference(
+  romans: &mut Vec<RomanChar>,
+  yomigana: &[char],
+  preference: &RomanPreference,
+) -> Result<(), RomanParseError> {
+  let mut parsed = vec![];
+  parse_chars(&mut parsed, yomigana, preference)?;
+  merge_extensions(&mut parsed, yomigana, preference);
+  romans.append(&mut parsed);
+  Ok(())
+}
+
+// 入力方式が仮名の並びをひと打ちにまとめているときは、その範囲の文字をまとめる
+fn merge_extensions(
+  romans: &mut Vec<RomanChar>,
+  yomigana: &[char],
+  preference: &RomanPreference,
+) {
+  let max_kana_len = preference.max_kana_len();
+  let mut merged = vec![];
+  let mut kana_start = 0;
+  let mut index = 0;
+  while index < romans.len() {
+    let mut longest = None;
+    let mut kana_end = kana_start + romans[index].kana_len();
+    for end in index + 2..=romans.len() {
+      kana_end += romans[end - 1].kana_len();
+      if max_kana_len < kana_end - kana_start {
+        break;
+      }
+      let kana: String =
+        yomigana[kana_start..kana_end].iter().collect();
+      let extensions = preference.extensions(&kana);
+      if !extensions.is_empty() {
+        longest = Some((end, kana_end, extensions));
+      }
+    }
+    if let Some((end, kana_end, extensions)) = longest {
+      let mut roman = RomanChar::concat(&romans[index..end]);
+      for style in extensions {
+        roman.prefer_or_add(&style);
+      }
+      merged.push(roman);
+      index = end;
+      kana_start = kana_end;
+    } else {
+      kana_start += romans[index].kana_len();
+      merged.push(romans[index].clone());
+      index += 1;
+    }
+  }
+  *romans = merged;
+}
+
+fn parse_chars(
   romans: &mut Vec<RomanChar>,
   mut yomigana: &[char],
+  preference: &RomanPreference,
 ) -> Result<(), RomanParseError> {
   while !yomigana.is_empty() {
     let replaced_count = match yomigana {
@@ -487,7 +549,7 @@ pub fn parse(
         1
       }
       ['つ', ..] => {
-        romans.push(RomanChar::new(&["tu"]));
+        romans.push(RomanChar::new(&["tu", "tsu"]));
         1
       }
       ['て', ..] => {
@@ -527,7 +589,7 @@ pub fn parse(
         1
       }
       ['ふ', ..] => {
-        romans.push(RomanChar::new(&["hu"]));
+        romans.push(RomanChar::new(&["hu", "fu"]));
         1
       }
       ['へ', ..] => {
@@ -763,7 +825,12 @@ pub fn parse(
         )));
       }
     };
-    yomigana = yomigana.split_at(replaced_count).1;
+    let (replaced, rest) = yomigana.split_at(replaced_count);
+    if let Some(roman) = romans.last_mut() {
+      roman.set_kana_len(replaced_count);
+      preference.apply(replaced, roman);
+    }
+    yomigana = rest;
   }
   Ok(())
 }
@@ -910,3 +977,31 @@ fn chocolate_balls() -> Result<(), RomanParseError> {
   }
   Ok(())
 }
+
+#[test]
+fn azik() -> Result<(), RomanParseError> {
+  use super::InputScheme;
+  let mut parsed: Vec<RomanChar> = vec![];
+  parse_with_preference(
+    &mut parsed,
+    "かんこうあんない".chars().collect::<Vec<char>>().as_slice(),
+    &RomanPreference::default().scheme(InputScheme::Azik),
+  )?;
+  for (expected, actual) in [
+    vec!["kz", "kaq", "kan"],
+    vec!["kp", "kou"],
+    vec!["a"],
+    vec!["q", "nn"],
+    vec!["nq", "nai"],
+  ]
+  .iter()
+  .zip(parsed.iter())
+  {
+    for (expected, actual) in expected.iter().zip(actual.styles()) {
+      assert_eq!(expected, actual);
+    }
+    assert_eq!(expected.len(), actual.styles().len());
+  }
+  assert_eq!(5, parsed.len());
+  Ok(())
+}
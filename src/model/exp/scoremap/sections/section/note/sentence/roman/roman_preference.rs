@@ -0,0 +1,223 @@
+use super::{InputScheme, RomanChar};
+use std::{
+  collections::HashMap,
+  fmt::{Display, Formatter},
+  str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RomanBaseStyle {
+  Default,
+  Hepburn,
+  Kunrei,
+}
+
+const HEPBURN: &[(&str, &[&str])] = &[
+  ("し", &["shi"]),
+  ("ち", &["chi"]),
+  ("つ", &["tsu"]),
+  ("ふ", &["fu"]),
+  ("じ", &["ji"]),
+  ("しゃ", &["sha"]),
+  ("しゅ", &["shu"]),
+  ("しょ", &["sho"]),
+  ("ちゃ", &["cha"]),
+  ("ちゅ", &["chu"]),
+  ("ちょ", &["cho"]),
+  ("じゃ", &["ja"]),
+  ("じゅ", &["ju"]),
+  ("じょ", &["jo"]),
+];
+
+const KUNREI: &[(&str, &[&str])] = &[
+  ("し", &["si"]),
+  ("ち", &["ti"]),
+  ("つ", &["tu"]),
+  ("ふ", &["hu"]),
+  ("じ", &["zi"]),
+  ("しゃ", &["sya"]),
+  ("しゅ", &["syu"]),
+  ("しょ", &["syo"]),
+  ("ちゃ", &["tya"]),
+  ("ちゅ", &["tyu"]),
+  ("ちょ", &["tyo"]),
+  ("じゃ", &["zya", "jya"]),
+  ("じゅ", &["zyu", "jyu"]),
+  ("じょ", &["zyo", "jyo"]),
+];
+
+#[derive(Debug)]
+pub enum RomanPreferenceError {
+  UnknownBaseStyle(String),
+  UnknownScheme(String),
+  InvalidLine { line_num: usize },
+}
+
+// 入力候補のうち、どの綴りを案内に出すかの好み
+#[derive(Debug, Clone, PartialEq)]
+pub struct RomanPreference {
+  base: RomanBaseStyle,
+  scheme: InputScheme,
+  custom: HashMap<String, String>,
+}
+
+impl Default for RomanPreference {
+  fn default() -> Self {
+    Self::new(RomanBaseStyle::Default)
+  }
+}
+
+impl RomanPreference {
+  pub fn new(base: RomanBaseStyle) -> Self {
+    Self {
+      base,
+      scheme: InputScheme::Standard,
+      custom: HashMap::new(),
+    }
+  }
+
+  pub fn scheme(mut self, scheme: InputScheme) -> Self {
+    self.scheme = scheme;
+    self
+  }
+
+  pub fn prefer(mut self, kana: &str, style: &str) -> Self {
+    self.custom.insert(kana.into(), style.into());
+    self
+  }
+
+  pub fn base(&self) -> RomanBaseStyle {
+    self.base
+  }
+
+  // 入力方式と個別の指定で、その仮名の並びに追加する綴りを優先度の低い順に返す
+  pub fn extensions(&self, kana: &str) -> Vec<String> {
+    let mut styles = self.scheme.styles(kana);
+    styles.extend(self.custom.get(kana).cloned());
+    styles
+  }
+
+  pub fn max_kana_len(&self) -> usize {
+    self
+      .custom
+      .keys()
+      .map(|kana| kana.chars().count())
+      .chain(Some(self.scheme.max_kana_len()))
+      .max()
+      .unwrap_or_default()
+  }
+
+  pub fn apply(&self, kana: &[char], roman: &mut RomanChar) {
+    let kana: String = kana.iter().collect();
+    let table: &[(&str, &[&str])] = match self.base {
+      RomanBaseStyle::Default => &[],
+      RomanBaseStyle::Hepburn => HEPBURN,
+      RomanBaseStyle::Kunrei => KUNREI,
+    };
+    if let Some((_, styles)) =
+      table.iter().find(|(key, _)| *key == kana)
+    {
+      for style in styles.iter() {
+        if roman.prefer(style) {
+          break;
+        }
+      }
+    }
+    for style in self.extensions(&kana) {
+      roman.prefer_or_add(&style);
+    }
+  }
+}
+
+impl FromStr for RomanBaseStyle {
+  type Err = RomanPreferenceError;
+
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    match name {
+      "default" => Ok(RomanBaseStyle::Default),
+      "hepburn" => Ok(RomanBaseStyle::Hepburn),
+      "kunrei" => Ok(RomanBaseStyle::Kunrei),
+      _ => Err(RomanPreferenceError::UnknownBaseStyle(name.into())),
+    }
+  }
+}
+
+impl Display for RomanBaseStyle {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      RomanBaseStyle::Default => "default",
+      RomanBaseStyle::Hepburn => "hepburn",
+      RomanBaseStyle::Kunrei => "kunrei",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+// `base hepburn` の行で基本の方式を、`scheme azik` の行で入力方式を、
+// `し si` の行で仮名ごとの綴りを指定する
+impl FromStr for RomanPreference {
+  type Err = RomanPreferenceError;
+
+  fn from_str(profile: &str) -> Result<Self, Self::Err> {
+    let mut preference = RomanPreference::default();
+    for (line_num, line) in profile.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let mut words = line.split_whitespace();
+      match (words.next(), words.next(), words.next()) {
+        (Some("base"), Some(base), None) => {
+          preference.base = base.parse()?;
+        }
+        (Some("scheme"), Some(scheme), None) => {
+          preference.scheme = scheme.parse()?;
+        }
+        (Some(kana), Some(style), None) => {
+          preference = preference.prefer(kana, style);
+        }
+        _ => {
+          return Err(RomanPreferenceError::InvalidLine {
+            line_num: line_num + 1,
+          })
+        }
+      }
+    }
+    Ok(preference)
+  }
+}
+
+#[test]
+fn hepburn() {
+  let preference = RomanPreference::new(RomanBaseStyle::Hepburn);
+  let mut shi = RomanChar::new(&["si", "shi"]);
+  preference.apply(&['し'], &mut shi);
+  assert_eq!("shi", shi.determined_style());
+
+  let mut tea = RomanChar::new(&["tya", "cha", "cya"]);
+  preference.apply(&['ち', 'ゃ'], &mut tea);
+  assert_eq!("cha", tea.determined_style());
+  assert_eq!(&["cha", "tya", "cya"], tea.styles());
+}
+
+#[test]
+fn custom_profile() -> Result<(), RomanPreferenceError> {
+  let preference: RomanPreference = r#"
+# 訓令式をもとに、ちゃ行だけ cy で打つ
+base kunrei
+ちゃ cya
+"#
+  .parse()?;
+  assert_eq!(RomanBaseStyle::Kunrei, preference.base());
+
+  let mut tea = RomanChar::new(&["tya", "cha", "cya"]);
+  preference.apply(&['ち', 'ゃ'], &mut tea);
+  assert_eq!("cya", tea.determined_style());
+
+  let mut ja = RomanChar::new(&["ja", "jya"]);
+  preference.apply(&['じ', 'ゃ'], &mut ja);
+  assert_eq!("jya", ja.determined_style());
+
+  assert!("base azik".parse::<RomanPreference>().is_err());
+  Ok(())
+}
@@ -1,4 +1,6 @@
-use super::{parse, RomanChar, RomanParseError};
+use super::{
+  parse_with_preference, RomanChar, RomanParseError, RomanPreference,
+};
 use std::fmt::{Debug, Formatter};
 
 #[derive(Clone, PartialEq)]
@@ -26,9 +28,16 @@ impl Debug for RomanStr {
 
 impl RomanStr {
   pub fn new(yomigana: &str) -> Result<Self, RomanParseError> {
+    Self::with_preference(yomigana, &RomanPreference::default())
+  }
+
+  pub fn with_preference(
+    yomigana: &str,
+    preference: &RomanPreference,
+  ) -> Result<Self, RomanParseError> {
     let chars: Vec<char> = yomigana.chars().collect();
     let mut parsed: Vec<RomanChar> = vec![];
-    parse(&mut parsed, chars.as_slice())?;
+    parse_with_preference(&mut parsed, chars.as_slice(), preference)?;
     Ok(RomanStr {
       yomigana: yomigana.into(),
       chars: parsed,
@@ -37,12 +46,26 @@ impl RomanStr {
     })
   }
 
+  pub fn literal(text: &str) -> Self {
+    RomanStr {
+      yomigana: text.into(),
+      chars: text.chars().map(RomanChar::literal).collect(),
+      inputting_char: 0,
+      inputted: String::new(),
+    }
+  }
+
+  pub fn yomigana(&self) -> &str {
+    &self.yomigana
+  }
+
   fn inputted_yomigana_index(&self) -> usize {
     self
       .chars
       .iter()
       .take_while(|c| c.completed_input())
-      .count()
+      .map(|c| c.kana_len())
+      .sum()
   }
 
   pub fn will_input_yomigana(
@@ -67,6 +90,28 @@ impl RomanStr {
       .into()
   }
 
+  // いま打っている仮名と、それを打てる綴りの候補
+  pub fn alternatives(&self) -> Option<(String, Vec<String>)> {
+    let current = self.chars.get(self.inputting_char)?;
+    let kana = self
+      .will_input_yomigana()
+      .take(current.kana_len())
+      .collect();
+    let styles = current
+      .acceptable_styles()
+      .into_iter()
+      .map(Into::into)
+      .collect();
+    Some((kana, styles))
+  }
+
+  pub fn next_keys(&self) -> Vec<char> {
+    self
+      .chars
+      .get(self.inputting_char)
+      .map_or(vec![], |current| current.next_keys())
+  }
+
   pub fn inputted_roman(&self) -> &str {
     &self.inputted
   }
@@ -184,3 +229,83 @@ fn small() -> Result<(), RomanParseError> {
   assert_eq!(hello.will_input_roman(), "");
   Ok(())
 }
+
+#[test]
+fn photograph() -> Result<(), RomanParseError> {
+  use super::RomanBaseStyle;
+  let photo = RomanStr::with_preference(
+    "しゃしんをとる",
+    &RomanPreference::new(RomanBaseStyle::Hepburn),
+  )?;
+  assert_eq!(photo.will_input_roman(), "shashinwotoru");
+  let photo = RomanStr::with_preference(
+    "しゃしんをとる",
+    &RomanPreference::new(RomanBaseStyle::Kunrei),
+  )?;
+  assert_eq!(photo.will_input_roman(), "syasinwotoru");
+  Ok(())
+}
+
+#[test]
+fn azik_hello() -> Result<(), RomanParseError> {
+  use super::InputScheme;
+  let mut hello = RomanStr::with_preference(
+    "こんにちは",
+    &RomanPreference::default().scheme(InputScheme::Azik),
+  )?;
+  assert_eq!(hello.will_input_roman(), "klnitiha");
+  assert!(hello.input('k'));
+  assert!(hello.input('o'));
+  assert!(hello.input('n'));
+  assert!(hello.input('n'));
+  assert_eq!(hello.will_input_roman(), "nitiha");
+  assert_eq!(
+    hello.will_input_yomigana().collect::<String>(),
+    "にちは"
+  );
+
+  let mut hello = RomanStr::with_preference(
+    "こんにちは",
+    &RomanPreference::default().scheme(InputScheme::Azik),
+  )?;
+  assert!(hello.input('k'));
+  assert!(hello.input('l'));
+  assert_eq!(hello.inputted_yomigana().collect::<String>(), "こん");
+  assert!(hello.input('n'));
+  assert!(hello.input('i'));
+  assert_eq!(hello.will_input_roman(), "tiha");
+  Ok(())
+}
+
+#[test]
+fn act_typing() -> Result<(), RomanParseError> {
+  use super::InputScheme;
+  let mut word = RomanStr::with_preference(
+    "こうかん",
+    &RomanPreference::default().scheme(InputScheme::Act),
+  )?;
+  assert_eq!(word.will_input_roman(), "c,c;");
+  assert!(word.input('c'));
+  assert!(word.input(','));
+  assert_eq!(word.inputted_yomigana().collect::<String>(), "こう");
+  assert!(word.input('k'));
+  assert!(word.input('a'));
+  assert_eq!(word.will_input_roman(), "n");
+  Ok(())
+}
+
+#[test]
+fn literal() {
+  let mut text = RomanStr::literal("Hi, you!");
+  assert_eq!(text.will_input_roman(), "Hi, you!");
+  assert!(!text.input('h'));
+  assert!(text.input('H'));
+  assert!(text.input('i'));
+  assert!(text.input(','));
+  assert!(text.input(' '));
+  assert_eq!(text.inputted_roman(), "Hi, ");
+  for typed in "you!".chars() {
+    assert!(text.input(typed));
+  }
+  assert!(text.completed());
+}
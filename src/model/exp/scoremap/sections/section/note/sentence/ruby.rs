@@ -0,0 +1,223 @@
+use std::ops::Range;
+
+// 歌詞に `千本桜(せんぼんざくら)` のように書いたルビ
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ruby {
+  // ルビを取り除いた歌詞での、親文字の範囲 (文字単位)
+  pub base: Range<usize>,
+  pub reading: String,
+  // 読み仮名の中で、この読みにあたる範囲 (文字単位)
+  pub yomigana: Range<usize>,
+}
+
+// 画面に並べる単位。ルビの付いた親文字か、ルビのない文字の並び
+#[derive(Debug, Clone, PartialEq)]
+pub struct RubyPiece {
+  pub base: String,
+  pub reading: Option<String>,
+  // 読みをすべて打ち終えたか
+  pub typed: bool,
+}
+
+fn is_kanji(c: char) -> bool {
+  matches!(c, '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}')
+    || matches!(c, '々' | '〆' | 'ヶ' | '〇')
+}
+
+fn is_kana(c: char) -> bool {
+  matches!(c, 'ぁ'..='ゖ' | 'ァ'..='ヺ' | 'ー')
+}
+
+fn is_open(c: char) -> bool {
+  c == '(' || c == '（'
+}
+
+fn is_close(c: char) -> bool {
+  c == ')' || c == '）'
+}
+
+fn is_bar(c: char) -> bool {
+  c == '|' || c == '｜'
+}
+
+// 読み仮名はひらがなで書くので、カタカナのルビもひらがなにして比べる
+fn to_hiragana(c: char) -> char {
+  match c {
+    'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+    _ => c,
+  }
+}
+
+// open の位置の括弧の中が仮名だけなら、その読みと閉じ括弧の位置を返す
+fn reading_at(
+  chars: &[char],
+  open: usize,
+) -> Option<(String, usize)> {
+  let close =
+    (open + 1..chars.len()).find(|&i| is_close(chars[i]))?;
+  let reading = &chars[open + 1..close];
+  if reading.is_empty() || !reading.iter().all(|&c| is_kana(c)) {
+    return None;
+  }
+  Some((reading.iter().collect(), close))
+}
+
+// 歌詞からルビを取り除いた文字列と、親文字の範囲と読みの組を返す。
+// 親文字は括弧の直前に続く漢字で、`|` や `｜` で始まりを明示することもできる。
+// 中身が仮名でない括弧は、そのまま歌詞として残す
+pub fn strip_ruby(
+  lyrics: &str,
+) -> (String, Vec<(Range<usize>, String)>) {
+  let chars: Vec<char> = lyrics.chars().collect();
+  let mut text: Vec<char> = vec![];
+  let mut spans: Vec<(Range<usize>, String)> = vec![];
+  let mut index = 0;
+  while index < chars.len() {
+    let c = chars[index];
+    if is_bar(c) {
+      let explicit = (index + 1..chars.len())
+        .find(|&i| is_open(chars[i]) || is_bar(chars[i]))
+        .filter(|&open| index + 1 < open && is_open(chars[open]))
+        .and_then(|open| {
+          reading_at(&chars, open).map(|found| (open, found))
+        });
+      if let Some((open, (reading, close))) = explicit {
+        let start = text.len();
+        text.extend(&chars[index + 1..open]);
+        spans.push((start..text.len(), reading));
+        index = close + 1;
+        continue;
+      }
+    }
+    if is_open(c) {
+      // 前のルビの親文字までは遡らない
+      let last_end = spans.last().map_or(0, |(base, _)| base.end);
+      let base_len = text[last_end..]
+        .iter()
+        .rev()
+        .take_while(|&&c| is_kanji(c))
+        .count();
+      if let (true, Some((reading, close))) =
+        (0 < base_len, reading_at(&chars, index))
+      {
+        spans.push((text.len() - base_len..text.len(), reading));
+        index = close + 1;
+        continue;
+      }
+    }
+    text.push(c);
+    index += 1;
+  }
+  (text.into_iter().collect(), spans)
+}
+
+// ルビの読みを読み仮名の先頭から順に探して、読み仮名での範囲を割り当てる。
+// 見つからない読みがあれば None を返す
+pub fn align_ruby(
+  spans: Vec<(Range<usize>, String)>,
+  yomigana: &str,
+) -> Option<Vec<Ruby>> {
+  let yomigana: Vec<char> =
+    yomigana.chars().map(to_hiragana).collect();
+  let mut cursor = 0;
+  spans
+    .into_iter()
+    .map(|(base, reading)| {
+      let target: Vec<char> =
+        reading.chars().map(to_hiragana).collect();
+      let last_start = yomigana.len().checked_sub(target.len())?;
+      let start = (cursor..=last_start).find(|&start| {
+        yomigana[start..start + target.len()] == target
+      })?;
+      cursor = start + target.len();
+      Some(Ruby {
+        base,
+        reading,
+        yomigana: start..cursor,
+      })
+    })
+    .collect()
+}
+
+// ルビのない文字もまとめて、歌詞を先頭から並べる
+pub fn ruby_pieces(
+  origin: &str,
+  ruby: &[Ruby],
+  inputted_yomigana: usize,
+) -> Vec<RubyPiece> {
+  let chars: Vec<char> = origin.chars().collect();
+  let mut pieces = vec![];
+  let mut cursor = 0;
+  for Ruby {
+    base,
+    reading,
+    yomigana,
+  } in ruby
+  {
+    if cursor < base.start {
+      pieces.push(RubyPiece {
+        base: chars[cursor..base.start].iter().collect(),
+        reading: None,
+        typed: false,
+      });
+    }
+    pieces.push(RubyPiece {
+      base: chars[base.clone()].iter().collect(),
+      reading: Some(reading.clone()),
+      typed: yomigana.end <= inputted_yomigana,
+    });
+    cursor = base.end;
+  }
+  if cursor < chars.len() {
+    pieces.push(RubyPiece {
+      base: chars[cursor..].iter().collect(),
+      reading: None,
+      typed: false,
+    });
+  }
+  pieces
+}
+
+#[test]
+fn ruby() {
+  let (text, spans) = strip_ruby(
+    "千本桜(せんぼんざくら)夜ニ紛（まぎ）レ|Happy(ハッピー)な(顔)",
+  );
+  assert_eq!("千本桜夜ニ紛レHappyな(顔)", text);
+  assert_eq!(
+    vec![
+      (0..3, "せんぼんざくら".to_string()),
+      (5..6, "まぎ".into()),
+      (7..12, "ハッピー".into()),
+    ],
+    spans
+  );
+
+  let ruby =
+    align_ruby(spans, "せんぼんざくらよるにまぎれはっぴーなかお")
+      .unwrap();
+  assert_eq!(0..7, ruby[0].yomigana);
+  assert_eq!(10..12, ruby[1].yomigana);
+  assert_eq!(13..17, ruby[2].yomigana);
+  assert_eq!(
+    None,
+    align_ruby(vec![(0..1, "さくら".into())], "せんぼん")
+  );
+
+  let pieces = ruby_pieces(&text, &ruby, 7);
+  assert_eq!(
+    vec![
+      ("千本桜", true),
+      ("夜ニ", false),
+      ("紛", false),
+      ("レ", false),
+      ("Happy", false),
+      ("な(顔)", false),
+    ],
+    pieces
+      .iter()
+      .map(|piece| (piece.base.as_str(), piece.typed))
+      .collect::<Vec<_>>()
+  );
+  assert_eq!(None, pieces[1].reading);
+}
@@ -467,3 +467,214 @@ fn case2() -> Result<(), super::ScoremapError> {
 
   Ok(())
 }
+
+#[test]
+fn raw_text() -> Result<(), super::ScoremapError> {
+  use super::sections::section::note::{
+    sentence::Sentence, Note, NoteContent,
+  };
+  use crate::model::exp::time::Duration;
+  let score = super::Scoremap::from_str(
+    r#"
+:title TEST
+:song_data void.ogg
+
+[start]
+*1.0
+打鍵テスト
+:だけんてすと
+
+*2.0
+=Hello, world!
+
+*3.0
+こんにちは
+=Good afternoon.
+
+*4.0
+[end]
+"#,
+    |config| config,
+  )?;
+  let mut dur = Duration::new(0.0, 1.0).unwrap();
+  let expected_notes = [
+    Note::blank(dur.clone()),
+    Note::sentence(
+      dur.following_replace(1.0),
+      Sentence::new("打鍵テスト", "だけんてすと")?,
+    ),
+    Note::sentence(
+      dur.following_replace(1.0),
+      Sentence::raw_text("Hello, world!", "Hello, world!"),
+    ),
+    Note::sentence(
+      dur.following_replace(1.0),
+      Sentence::raw_text("こんにちは", "Good afternoon."),
+    ),
+    Note::blank(dur.following_replace(1.0)),
+  ];
+  let actual: Vec<_> =
+    score.sections.iter().flat_map(|s| s.iter()).collect();
+  assert_eq!(expected_notes.len(), actual.len());
+  for (expected, actual) in expected_notes.iter().zip(actual) {
+    assert_eq!(expected.content(), actual.content());
+    assert_eq!(expected.duration(), actual.duration());
+  }
+  if let NoteContent::Sentence { sentence, .. } =
+    expected_notes[3].content()
+  {
+    assert_eq!("Good afternoon.", sentence.keys().will_input);
+  }
+
+  assert!(super::Scoremap::from_str(
+    "[start]\n*1.0\n=こんにちは\n*2.0\n[end]\n",
+    |config| config,
+  )
+  .is_err());
+  Ok(())
+}
+
+#[test]
+fn unaligned_ruby() -> Result<(), super::ScoremapError> {
+  use super::sections::section::note::{
+    sentence::Sentence, NoteContent,
+  };
+  let score = super::Scoremap::from_str(
+    r#"
+:title TEST
+:song_data void.ogg
+
+[start]
+*1.0
+本気(マジ)で
+:ほんきで
+
+*2.0
+[end]
+"#,
+    |config| config,
+  )?;
+  let sentences: Vec<_> = score
+    .sections
+    .iter()
+    .flat_map(|s| s.iter())
+    .filter_map(|note| match note.content() {
+      NoteContent::Sentence { sentence, .. } => {
+        Some(sentence.clone())
+      }
+      _ => None,
+    })
+    .collect();
+  assert_eq!(vec![Sentence::new("本気で", "ほんきで")?], sentences);
+  Ok(())
+}
+
+#[test]
+fn stable_note_id() -> Result<(), super::ScoremapError> {
+  let chart = |yomigana: &str| {
+    super::Scoremap::from_str(
+      &format!(
+        "[start]\n*1.0\n打鍵テスト\n:{}\n*2.0\n[end]\n",
+        yomigana
+      ),
+      |config| config,
+    )
+  };
+  let first = chart("だけんてすと")?;
+  let second = chart("だけんてすと")?;
+  let other = chart("だけんてすとお")?;
+
+  let ids = |score: &super::Scoremap| -> Vec<_> {
+    score
+      .sections
+      .iter()
+      .flat_map(|s| s.iter())
+      .map(|note| note.id())
+      .collect()
+  };
+  assert_eq!(ids(&first), ids(&second));
+  assert_ne!(ids(&first), ids(&other));
+  let first_ids = ids(&first);
+  assert_eq!((first_ids[1].section, first_ids[1].note), (0, 1));
+  let found = first.sections.note_by_id(&first_ids[1]).unwrap();
+  assert_eq!(first_ids[1], found.id());
+  assert!(first.sections.note_by_id(&ids(&other)[1]).is_none());
+  Ok(())
+}
+
+#[cfg(test)]
+fn long_chart(
+  notes: usize,
+) -> Result<super::Scoremap, super::ScoremapError> {
+  // 0.5 秒ごとに並べる。60 秒を超える分は分指定で書く
+  let time_spec = |index: usize| {
+    let (minutes, step) = (index / 120, index % 120);
+    let seconds = step as f64 * 0.5;
+    if step == 0 {
+      format!("|{}\n*{}\n", minutes, seconds)
+    } else {
+      format!("*{}\n", seconds)
+    }
+  };
+  let mut chart = String::from("[start]\n");
+  for index in 0..notes {
+    let (section, offset) = (index / 50, index % 50);
+    if offset == 0 {
+      chart.push_str(&format!("@S{}\n", section));
+    }
+    chart.push_str(&time_spec(index));
+    chart.push_str("あ\n:あ\n");
+  }
+  chart.push_str(&time_spec(notes));
+  chart.push_str("[end]\n");
+  super::Scoremap::from_str(&chart, |config| config)
+}
+
+#[test]
+fn seek_long_chart() -> Result<(), super::ScoremapError> {
+  let mut sections = long_chart(2000)?.sections;
+  // 全ノーツをなめる素朴な探し方と結果を比べる
+  let expected_id = |sections: &super::sections::Sections,
+                     time: f64| {
+    sections
+      .iter()
+      .flat_map(|section| section.iter())
+      .find(|note| note.duration().includes(&time.into()))
+      .map(|note| note.id())
+  };
+  for &time in &[0.2, 600.2, 12.7, 999.9, 600.2, 30.0, 29.9, 1.0] {
+    let expected = expected_id(&sections, time);
+    let actual = sections.update(time.into()).map(|s| s.id());
+    assert_eq!(expected, actual, "time: {}", time);
+    assert!(actual.is_some());
+  }
+  assert!(sections.update(2000.0.into()).is_none());
+  Ok(())
+}
+
+// cargo test --release -- --ignored bench_update で実行する
+#[test]
+#[ignore]
+fn bench_update() -> Result<(), super::ScoremapError> {
+  const NOTES: usize = 5000;
+  const FRAMES: usize = 60 * 60 * 45;
+  let mut sections = long_chart(NOTES)?.sections;
+  let end = NOTES as f64 * 0.5;
+  let start = std::time::Instant::now();
+  for frame in 0..FRAMES {
+    let time = end * frame as f64 / FRAMES as f64;
+    assert!(sections.update(time.into()).is_some());
+  }
+  // 早送りのように、大きく時刻を飛ばしながら探す
+  for frame in 0..FRAMES {
+    let time = end * ((frame * 7919) % FRAMES) as f64 / FRAMES as f64;
+    assert!(sections.update(time.into()).is_some());
+  }
+  println!(
+    "{} notes, {} updates: {:?}",
+    NOTES,
+    FRAMES * 2,
+    start.elapsed()
+  );
+  Ok(())
+}
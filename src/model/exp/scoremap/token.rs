@@ -9,6 +9,7 @@ pub enum TokenContent {
   Command(String),
   Lyrics(String),
   Yomigana(RomanStr),
+  RawText(String),
   Caption(String),
   Section(String),
   Time(MinuteSecond),
@@ -50,6 +50,40 @@ impl Duration {
     }
   }
 
+  pub fn start(&self) -> Seconds {
+    self.from
+  }
+
+  pub fn end(&self) -> Seconds {
+    self.to
+  }
+
+  pub fn length(&self) -> Seconds {
+    self.to - self.from
+  }
+
+  // origin より後ろの部分だけを ratio 倍に引き伸ばす。origin より前は動かさない
+  pub fn scale_from(&self, origin: Seconds, ratio: f64) -> Self {
+    let scale = |time: Seconds| {
+      if time <= origin {
+        time
+      } else {
+        origin + (time - origin) * ratio
+      }
+    };
+    Self {
+      from: scale(self.from),
+      to: scale(self.to),
+    }
+  }
+
+  pub fn shift(&self, amount: Seconds) -> Self {
+    Self {
+      from: self.from + amount,
+      to: self.to + amount,
+    }
+  }
+
   pub fn includes(&self, time: &Seconds) -> bool {
     self.from <= *time && *time < self.to
   }
@@ -81,6 +115,17 @@ fn duration() -> DurationResult<()> {
   let duration = duration.following(2.4);
   assert_eq!(3.1, duration.from);
   assert_eq!(5.5, duration.to);
+
+  let duration = duration.scale_from(4.1.into(), 2.0);
+  assert_eq!(3.1, duration.from);
+  assert_eq!(6.9, duration.to);
+  let duration = duration.scale_from(1.1.into(), 0.5);
+  assert_eq!(2.1, duration.from);
+  assert_eq!(4.0, duration.to);
+
+  let duration = duration.shift(Seconds::new(-0.5));
+  assert_eq!(1.6, duration.from);
+  assert_eq!(3.5, duration.to);
   Ok(())
 }
 
@@ -1,7 +1,7 @@
 use std::{
   cmp::Ordering,
   fmt::{Display, Formatter, Result},
-  ops::{Add, AddAssign, Div, Sub, SubAssign},
+  ops::{Add, AddAssign, Div, Mul, Sub, SubAssign},
 };
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -59,6 +59,14 @@ impl SubAssign for Seconds {
   }
 }
 
+impl Mul<f64> for Seconds {
+  type Output = Self;
+
+  fn mul(self, rhs: f64) -> Self::Output {
+    Self((self.0 as f64 * rhs).round() as i64)
+  }
+}
+
 impl Div for Seconds {
   type Output = f64;
 
@@ -1,17 +1,28 @@
 use super::exp::{
   game_activity::GameActivity,
-  note::TypeResult,
+  note::{NoteContent, NoteId, TypeResult},
   scoremap::{
     lexer::ScoremapLexError, MusicInfo, Scoremap, ScoremapError,
     ScoremapMetadata,
   },
-  sentence::{roman::RomanParseError, Sentence},
+  section::SectionStats,
+  sentence::{roman::RomanParseError, InputMethod, Sentence},
   time::Seconds,
 };
+use adaptive::AdaptiveDifficulty;
+pub use clock::{Clock, ManualClock, WallClock};
+pub use observer::MusicalTyperObserver;
+pub use play_log::{Keystroke, NoteOutcome, NoteResult, PlayLog};
 use std::io::Error;
 use MusicalTyperError::*;
 use MusicalTyperEvent::*;
 
+mod adaptive;
+pub mod calibration;
+mod clock;
+pub mod export;
+mod observer;
+mod play_log;
 #[cfg(test)]
 mod tests;
 
@@ -40,12 +51,20 @@ pub enum MusicalTyperEvent {
   CompletedSentence(Sentence),
   DidPerfectSection,
   Typed(MusicalTypeResult),
+  ChangedNote(NoteId),
+  StartedSection(usize),
+  EndedSection(SectionStats),
+  ChangedCaption(String),
+  ChangedCombo(u32),
+  // 加点や減点があったときの増減
+  AdjustedScore(i32),
   EndOfScore,
 }
 
 #[derive(Debug)]
 pub enum MusicalTyperError {
   SongDataNotFound,
+  InvalidOffset(String),
   FileReadError { reason: String },
   ScoremapBuildError(ScoremapError),
 }
@@ -83,6 +102,35 @@ pub struct MusicalTyperConfig {
   complete_sentence: Point,
   perfect_sentence: Point,
   perfect_section: Point,
+  wrong_type_damage: u32,
+  missed_sentence_damage: u32,
+  life: Option<u32>,
+  adaptive_practice: bool,
+  input_method: InputMethod,
+  user_offset: Seconds,
+}
+
+impl MusicalTyperConfig {
+  pub fn adaptive_practice(mut self, whether: bool) -> Self {
+    self.adaptive_practice = whether;
+    self
+  }
+
+  pub fn life(mut self, life: Option<u32>) -> Self {
+    self.life = life;
+    self
+  }
+
+  pub fn input_method(mut self, method: InputMethod) -> Self {
+    self.input_method = method;
+    self
+  }
+
+  // 音声や表示の遅れを補う、環境ごとのずれ。譜面の offset に足し合わせる
+  pub fn user_offset(mut self, offset: Seconds) -> Self {
+    self.user_offset = offset;
+    self
+  }
 }
 
 impl Default for MusicalTyperConfig {
@@ -94,6 +142,12 @@ impl Default for MusicalTyperConfig {
       complete_sentence: 50,
       perfect_sentence: 100,
       perfect_section: 300,
+      wrong_type_damage: 1,
+      missed_sentence_damage: 10,
+      life: None,
+      adaptive_practice: false,
+      input_method: InputMethod::Roman,
+      user_offset: Seconds::default(),
     }
   }
 }
@@ -104,32 +158,141 @@ pub struct MusicalTyper {
   current_time: Seconds,
   event_queue: Vec<MusicalTyperEvent>,
   config: MusicalTyperConfig,
+  adaptive: Option<AdaptiveDifficulty>,
+  clock: Box<dyn Clock>,
+  song_data: Option<String>,
+  observers: Vec<Box<dyn MusicalTyperObserver>>,
+  ended: bool,
+  log: PlayLog,
 }
 
+const ADAPTIVE_TARGET_COMPLETION: f64 = 0.9;
+
 impl MusicalTyper {
   pub fn new(
     score: Scoremap,
     config: MusicalTyperConfig,
   ) -> Result<Self, MusicalTyperError> {
-    let mut event_queue = vec![];
-    let activity = GameActivity::new(score.sections);
+    let mut sections = score.sections;
+    sections.set_input_method(config.input_method);
+    let chart_offset: Seconds = match score.metadata.get("offset") {
+      Some(offset) => offset
+        .parse::<f64>()
+        .map_err(|_| InvalidOffset(offset.clone()))?
+        .into(),
+      None => Seconds::default(),
+    };
+    sections.shift(chart_offset + config.user_offset);
+    let mut activity = GameActivity::new(sections);
+    activity.set_life(config.life);
 
     let metadata = score.metadata;
-    if let Some(song_data) = metadata.get("song_data") {
-      event_queue.push(PlayBgm(song_data.into()));
+    // 練習モードではノーツの長さが変わるので曲は流さない
+    let (adaptive, song_data) = if config.adaptive_practice {
+      (
+        Some(AdaptiveDifficulty::new(ADAPTIVE_TARGET_COMPLETION)),
+        None,
+      )
+    } else if let Some(song_data) = metadata.get("song_data") {
+      (None, Some(song_data.clone()))
     } else {
       return Err(SongDataNotFound);
-    }
+    };
 
     Ok(MusicalTyper {
       activity,
       metadata,
       current_time: 0.0.into(),
-      event_queue,
+      event_queue: vec![],
       config,
+      adaptive,
+      clock: Box::new(WallClock::new()),
+      song_data,
+      observers: vec![],
+      ended: false,
+      log: PlayLog::new(),
     })
   }
 
+  pub fn subscribe(
+    &mut self,
+    observer: impl MusicalTyperObserver + 'static,
+  ) {
+    self.observers.push(Box::new(observer));
+  }
+
+  fn publish(&mut self, events: &[MusicalTyperEvent]) {
+    for event in events {
+      self.log.record(self.current_time, event);
+    }
+    for observer in self.observers.iter_mut() {
+      for event in events {
+        observer.notify(event);
+      }
+    }
+  }
+
+  fn point(&mut self, amount: i32) {
+    if amount == 0 {
+      return;
+    }
+    self.activity.point(amount);
+    self.event_queue.push(AdjustedScore(amount));
+  }
+
+  fn push_combo_change(&mut self, prev_combo: u32) {
+    let combo = self.activity.score().combo;
+    if combo != prev_combo {
+      self.event_queue.push(ChangedCombo(combo));
+    }
+  }
+
+  // 準備の間は時刻を進めず、始めたときに曲を流して時計を動かす
+  #[must_use]
+  pub fn start(&mut self) -> Vec<MusicalTyperEvent> {
+    if !self.activity.is_before_start() {
+      return vec![];
+    }
+    self.activity.start();
+    self.clock.reset();
+    if let Some(song_data) = self.song_data.as_ref() {
+      self.event_queue.push(PlayBgm(song_data.clone()));
+    }
+    if let Some(id) = self.activity.current_note_id() {
+      self.event_queue.push(StartedSection(id.section));
+    }
+    self.set_time(0.0.into())
+  }
+
+  pub fn is_started(&self) -> bool {
+    !self.activity.is_before_start()
+  }
+
+  // 曲を流す譜面か。練習モードでは流さない
+  pub fn has_song(&self) -> bool {
+    self.song_data.is_some()
+  }
+
+  // 曲を流すときは、再生位置を測る時計に差し替える。
+  // 差し替えなければ、曲のない練習モードと同じく実時間で進む
+  pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+    self.clock = clock;
+  }
+
+  pub fn clock_time(&self) -> Seconds {
+    self.clock.now()
+  }
+
+  #[must_use]
+  pub fn tick(&mut self) -> Vec<MusicalTyperEvent> {
+    let now = self.clock.now();
+    self.set_time(now)
+  }
+
+  pub fn play_log(&self) -> &PlayLog {
+    &self.log
+  }
+
   pub fn activity(&self) -> &GameActivity {
     &self.activity
   }
@@ -143,40 +306,63 @@ impl MusicalTyper {
     let prev_completed = prev_sentence.completed();
     for typed in typed {
       use super::exp::section::note::TypeResult::*;
+      let prev_combo = self.activity.score().combo;
       let result = self.activity.input(typed);
       let point = match result {
         Succeed => self.config.correct_type as i32,
-        Mistaken => -(self.config.wrong_type as i32),
+        Mistaken => {
+          self.activity.damage(self.config.wrong_type_damage);
+          -(self.config.wrong_type as i32)
+        }
         _ => 0,
       };
-      self.activity.point(point);
       self.event_queue.push(Typed(result.into()));
+      self.point(point);
+      self.push_combo_change(prev_combo);
     }
     let curr_sentence = self.activity.current_sentence();
     let curr_completed = curr_sentence.completed();
 
-    let mut events = vec![];
+    let mut events = self.pack_events();
     if !prev_completed && curr_completed {
       if self
         .activity
         .current_section()
         .map_or(false, |section| 1.0 <= section.accuracy())
       {
-        self.activity.point(self.config.perfect_section as i32);
-        events.push(DidPerfectSection);
+        self.point(self.config.perfect_section as i32);
+        self.event_queue.push(DidPerfectSection);
       }
       if self
         .activity
         .current_note()
         .map_or(false, |note| 1.0 <= note.accuracy())
       {
-        self.activity.point(self.config.perfect_sentence as i32);
+        self.point(self.config.perfect_sentence as i32);
       }
-      self.activity.point(self.config.complete_sentence as i32);
-      events.push(CompletedSentence(prev_sentence));
+      self.point(self.config.complete_sentence as i32);
+      self.event_queue.push(CompletedSentence(prev_sentence));
     }
+    events.append(&mut self.event_queue);
+
+    self.publish(&events);
+    events
+  }
 
-    [self.pack_events(), events].concat()
+  // 打鍵ごとに、押された時刻まで進めてから入力する
+  #[must_use]
+  pub fn key_press_with_time(
+    &mut self,
+    typed: impl Iterator<Item = (char, Seconds)>,
+  ) -> Vec<MusicalTyperEvent> {
+    let mut events = vec![];
+    for (typed, time) in typed {
+      if self.current_time < time {
+        events.append(&mut self.set_time(time));
+      }
+      events.append(&mut self.key_press(std::iter::once(typed)));
+    }
+    events
   }
 
   #[must_use]
@@ -193,18 +379,94 @@ impl MusicalTyper {
     self.activity.update_time(self.current_time);
 
     if self.activity.is_game_over() {
-      return vec![EndOfScore];
+      // 終わりの知らせは、終わったときに一度だけ出す
+      let mut events = vec![];
+      if !self.ended {
+        self.ended = true;
+        if let Some(section) = self.activity.current_section() {
+          events.push(EndedSection(section.stats()));
+        }
+        events.push(EndOfScore);
+        self.publish(&events);
+      }
+      return events;
     }
 
     let curr_note_id = self.activity.current_note_id();
 
     let mut events = vec![];
+    if prev_note_id != curr_note_id {
+      self.adapt_upcoming(&prev_sentence);
+      self.push_note_change(prev_note_id, curr_note_id);
+    }
     if !completed && (prev_note_id != curr_note_id) {
-      self.activity.point(-(self.config.missed_sentence as i32));
+      let prev_combo = self.activity.score().combo;
+      self.point(-(self.config.missed_sentence as i32));
+      self.activity.damage(self.config.missed_sentence_damage);
+      self.activity.break_combo();
+      self.push_combo_change(prev_combo);
       events.push(MissedSentence(prev_sentence));
     }
 
-    [self.pack_events(), events].concat()
+    let events = [self.pack_events(), events].concat();
+    self.publish(&events);
+    events
+  }
+
+  fn push_note_change(
+    &mut self,
+    prev_id: Option<NoteId>,
+    curr_id: Option<NoteId>,
+  ) {
+    let curr_id = match curr_id {
+      Some(id) => id,
+      None => return,
+    };
+    if let Some(prev_id) = prev_id {
+      if prev_id.section != curr_id.section {
+        if let Some(stats) =
+          self.activity.section_stats(prev_id.section)
+        {
+          self.event_queue.push(EndedSection(stats));
+        }
+        self.event_queue.push(StartedSection(curr_id.section));
+      }
+    }
+    self.event_queue.push(ChangedNote(curr_id));
+    if let Some(NoteContent::Caption(caption)) =
+      self.activity.current_note().map(|note| note.content())
+    {
+      self.event_queue.push(ChangedCaption(caption.clone()));
+    }
+  }
+
+  pub fn observe_type_speed(&mut self, type_per_second: f64) {
+    if let Some(adaptive) = self.adaptive.as_mut() {
+      adaptive.observe_speed(type_per_second);
+    }
+  }
+
+  fn adapt_upcoming(&mut self, prev_sentence: &Sentence) {
+    let adaptive = if let Some(adaptive) = self.adaptive.as_mut() {
+      adaptive
+    } else {
+      return;
+    };
+    if !prev_sentence.origin().is_empty() {
+      adaptive.record(prev_sentence.completed());
+    }
+    let ratio = match self.activity.current_note() {
+      Some(note) => match note.content() {
+        NoteContent::Sentence { sentence, .. } => adaptive
+          .next_ratio(
+            sentence.keys().will_input.chars().count(),
+            note.duration().length(),
+          ),
+        _ => return,
+      },
+      None => return,
+    };
+    self.activity.scale_upcoming(ratio);
   }
 
   fn pack_events(&mut self) -> Vec<MusicalTyperEvent> {
@@ -216,10 +478,27 @@ impl MusicalTyper {
     res
   }
 
+  pub fn input_method(&self) -> InputMethod {
+    self.config.input_method
+  }
+
   pub fn current_time(&self) -> Seconds {
     self.current_time
   }
 
+  // これから始まる文を、始まるまでの時間と合わせて count 個まで返す
+  pub fn upcoming_sentences(
+    &self,
+    count: usize,
+  ) -> Vec<(Seconds, Sentence)> {
+    self
+      .activity
+      .upcoming_sentences(self.current_time, count)
+      .into_iter()
+      .map(|(start, sentence)| (start - self.current_time, sentence))
+      .collect()
+  }
+
   pub fn section_remaining_ratio(&self) -> f64 {
     self.activity.remaining_ratio(self.current_time)
   }
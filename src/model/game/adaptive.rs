@@ -0,0 +1,108 @@
+use crate::model::exp::time::Seconds;
+use std::collections::VecDeque;
+
+const OUTCOME_WINDOW: usize = 8;
+const COMPLETION_GAIN: f64 = 0.5;
+const MIN_STEP_RATIO: f64 = 0.8;
+const MAX_STEP_RATIO: f64 = 1.25;
+const MIN_TOTAL_STRETCH: f64 = 0.5;
+const MAX_TOTAL_STRETCH: f64 = 3.0;
+
+// 練習モードで、プレイヤーの打鍵速度と達成状況に合わせてノーツの長さを伸縮させる。
+#[derive(Debug, Clone)]
+pub struct AdaptiveDifficulty {
+  target_completion: f64,
+  outcomes: VecDeque<bool>,
+  type_per_second: f64,
+  total_stretch: f64,
+}
+
+impl AdaptiveDifficulty {
+  pub fn new(target_completion: f64) -> Self {
+    Self {
+      target_completion,
+      outcomes: VecDeque::new(),
+      type_per_second: 0.0,
+      total_stretch: 1.0,
+    }
+  }
+
+  pub fn observe_speed(&mut self, type_per_second: f64) {
+    self.type_per_second = type_per_second;
+  }
+
+  pub fn record(&mut self, completed: bool) {
+    self.outcomes.push_back(completed);
+    while OUTCOME_WINDOW < self.outcomes.len() {
+      self.outcomes.pop_front();
+    }
+  }
+
+  pub fn completion_rate(&self) -> f64 {
+    if self.outcomes.is_empty() {
+      return self.target_completion;
+    }
+    self.outcomes.iter().filter(|&&completed| completed).count()
+      as f64
+      / self.outcomes.len() as f64
+  }
+
+  // 次のノーツ以降に掛ける伸縮率を求めて、累積の伸縮率に反映する。
+  pub fn next_ratio(
+    &mut self,
+    roman_len: usize,
+    note_length: Seconds,
+  ) -> f64 {
+    let note_length = note_length.as_f64();
+    if roman_len == 0 || note_length <= 0.0 {
+      return 1.0;
+    }
+    let required_speed = roman_len as f64 / note_length;
+    let speed_ratio = if 0.0 < self.type_per_second {
+      required_speed / self.type_per_second
+    } else {
+      1.0
+    };
+    let correction = 1.0
+      + COMPLETION_GAIN
+        * (self.target_completion - self.completion_rate());
+    let ratio = (speed_ratio * correction)
+      .clamp(MIN_STEP_RATIO, MAX_STEP_RATIO);
+    let total = (self.total_stretch * ratio)
+      .clamp(MIN_TOTAL_STRETCH, MAX_TOTAL_STRETCH);
+    let ratio = total / self.total_stretch;
+    self.total_stretch = total;
+    ratio
+  }
+}
+
+#[test]
+fn stretch_for_slow_player() {
+  let mut adaptive = AdaptiveDifficulty::new(0.9);
+  adaptive.observe_speed(2.0);
+  let ratio = adaptive.next_ratio(8, 2.0.into());
+  assert!(1.0 < ratio);
+  assert!(ratio <= MAX_STEP_RATIO);
+
+  adaptive.observe_speed(8.0);
+  let ratio = adaptive.next_ratio(8, 2.0.into());
+  assert!(ratio < 1.0);
+  assert!(MIN_STEP_RATIO <= ratio);
+}
+
+#[test]
+fn react_to_completion() {
+  let mut adaptive = AdaptiveDifficulty::new(0.9);
+  adaptive.observe_speed(4.0);
+  for _ in 0..OUTCOME_WINDOW {
+    adaptive.record(false);
+  }
+  assert_eq!(0.0, adaptive.completion_rate());
+  assert!(1.0 < adaptive.next_ratio(8, 2.0.into()));
+
+  for _ in 0..OUTCOME_WINDOW {
+    adaptive.record(true);
+  }
+  assert_eq!(1.0, adaptive.completion_rate());
+  assert!(adaptive.next_ratio(8, 2.0.into()) < 1.0);
+}
@@ -0,0 +1,75 @@
+use crate::model::exp::time::Seconds;
+
+// 最初の数打はリズムに慣れるまでずれが大きいので数えない
+const WARM_UP_TAPS: usize = 2;
+
+// 一定の間隔で鳴らすクリックに合わせて打ってもらい、打鍵の平均的なずれを測る
+#[derive(Debug, Clone)]
+pub struct Calibration {
+  interval: Seconds,
+  clicks: usize,
+  taps: usize,
+  offsets: Vec<Seconds>,
+}
+
+impl Calibration {
+  pub fn new(interval: Seconds, clicks: usize) -> Self {
+    Self {
+      interval,
+      clicks,
+      taps: 0,
+      offsets: vec![],
+    }
+  }
+
+  // 最初のクリックは 1 間隔ぶん待ってから鳴らす
+  pub fn click_time(&self, index: usize) -> Seconds {
+    self.interval * (index + 1) as f64
+  }
+
+  pub fn clicks(&self) -> usize {
+    self.clicks
+  }
+
+  pub fn end_time(&self) -> Seconds {
+    self.click_time(self.clicks)
+  }
+
+  pub fn tap(&mut self, time: Seconds) {
+    let beats = time.as_f64() / self.interval.as_f64();
+    let nearest = (beats.round() as usize)
+      .saturating_sub(1)
+      .min(self.clicks.saturating_sub(1));
+    let offset = time - self.click_time(nearest);
+    // 隣のクリックとの中間より離れた打鍵は数えない
+    if self.interval.as_f64() / 2.0 < offset.as_f64().abs() {
+      return;
+    }
+    self.taps += 1;
+    if WARM_UP_TAPS < self.taps {
+      self.offsets.push(offset);
+    }
+  }
+
+  pub fn offset(&self) -> Option<Seconds> {
+    if self.offsets.is_empty() {
+      return None;
+    }
+    let sum: f64 =
+      self.offsets.iter().map(|offset| offset.as_f64()).sum();
+    Some((sum / self.offsets.len() as f64).into())
+  }
+}
+
+#[test]
+fn calibration() {
+  let mut calibration = Calibration::new(0.5.into(), 8);
+  assert!(calibration.offset().is_none());
+  for index in 0..calibration.clicks() {
+    let click = calibration.click_time(index);
+    calibration.tap(click + 0.08.into());
+  }
+  calibration.tap(6.0.into());
+  assert_eq!(0.08, calibration.offset().unwrap());
+  assert_eq!(4.5, calibration.end_time());
+}
@@ -0,0 +1,75 @@
+use crate::model::exp::time::Seconds;
+use std::{cell::Cell, rc::Rc, time::Instant};
+
+// 譜面上の現在時刻を知らせる時計
+pub trait Clock {
+  fn now(&self) -> Seconds;
+
+  // ゲームを始めるときに呼ばれる
+  fn reset(&mut self) {}
+}
+
+// 作られてからの実時間を測る
+pub struct WallClock {
+  start: Instant,
+}
+
+impl WallClock {
+  pub fn new() -> Self {
+    Self {
+      start: Instant::now(),
+    }
+  }
+}
+
+impl Default for WallClock {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Clock for WallClock {
+  fn now(&self) -> Seconds {
+    self.start.elapsed().as_secs_f64().into()
+  }
+
+  fn reset(&mut self) {
+    self.start = Instant::now();
+  }
+}
+
+// 手で時刻を進める。複製したものと時刻を共有する
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+  time: Rc<Cell<Seconds>>,
+}
+
+impl ManualClock {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn set(&self, time: Seconds) {
+    self.time.set(time);
+  }
+
+  pub fn advance(&self, amount: Seconds) {
+    self.time.set(self.time.get() + amount);
+  }
+}
+
+impl Clock for ManualClock {
+  fn now(&self) -> Seconds {
+    self.time.get()
+  }
+}
+
+#[test]
+fn manual() {
+  let clock = ManualClock::new();
+  let shared = clock.clone();
+  assert_eq!(0.0, clock.now());
+  shared.set(1.5.into());
+  clock.advance(0.25.into());
+  assert_eq!(1.75, shared.now());
+}
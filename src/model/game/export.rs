@@ -0,0 +1,234 @@
+use super::{MusicalTypeResult, PlayLog};
+use crate::model::exp::{
+  game_activity::GameScore, note::NoteId, scoremap::MusicInfo,
+};
+use std::{
+  fs, io,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+// 書き出し先を指定しなかったときのディレクトリ
+pub const DEFAULT_EXPORT_DIR: &str = "results";
+
+// 表計算ソフトなどで読み込めるように、遊んだ結果を書き出す
+pub struct ResultExport<'a> {
+  info: &'a MusicInfo,
+  score: &'a GameScore,
+  log: &'a PlayLog,
+}
+
+impl<'a> ResultExport<'a> {
+  pub fn new(
+    info: &'a MusicInfo,
+    score: &'a GameScore,
+    log: &'a PlayLog,
+  ) -> Self {
+    Self { info, score, log }
+  }
+
+  pub fn to_json(&self) -> String {
+    let score = self.score;
+    let mut json = String::from("{\n");
+    json += &format!(
+      "  \"title\": {},\n  \"song_author\": {},\n",
+      json_string(&self.info.title),
+      json_string(&self.info.song_author)
+    );
+    json += &format!(
+      "  \"score\": {{\"score_point\": {}, \"achievement_rate\": {}, \
+       \"accuracy\": {}, \"remaining_life\": {}, \"max_combo\": {}, \
+       \"correct_types\": {}, \"wrong_types\": {}}},\n",
+      score.score_point,
+      json_number(score.achievement_rate),
+      json_number(score.accuracy),
+      score
+        .remaining_life
+        .map_or_else(|| "null".into(), |life| life.to_string()),
+      score.max_combo,
+      score.correction_type_count(),
+      score.wrong_type_count()
+    );
+    let notes: Vec<_> = self
+      .log
+      .notes()
+      .iter()
+      .map(|note| {
+        format!(
+          "    {{\"id\": \"{}\", \"section\": {}, \"note\": {}, \
+           \"sentence\": {}, \"correct\": {}, \"wrong\": {}, \
+           \"outcome\": \"{}\"}}",
+          note.id,
+          note.id.section,
+          note.id.note,
+          json_string(&note.sentence),
+          note.correct,
+          note.wrong,
+          note.outcome.as_str()
+        )
+      })
+      .collect();
+    json += &format!("  \"notes\": [\n{}\n  ],\n", notes.join(",\n"));
+    let keystrokes: Vec<_> = self
+      .log
+      .keystrokes()
+      .iter()
+      .map(|keystroke| {
+        format!(
+          "    {{\"time\": {}, \"result\": \"{}\", \"note\": {}}}",
+          json_number(keystroke.time.as_f64()),
+          result_str(&keystroke.result),
+          keystroke.note.map_or_else(
+            || "null".into(),
+            |id| format!("\"{}\"", id)
+          )
+        )
+      })
+      .collect();
+    json += &format!(
+      "  \"keystrokes\": [\n{}\n  ]\n}}\n",
+      keystrokes.join(",\n")
+    );
+    json
+  }
+
+  pub fn score_csv(&self) -> String {
+    let score = self.score;
+    let mut csv = String::from(
+      "title,song_author,score_point,achievement_rate,accuracy,\
+       remaining_life,max_combo,correct_types,wrong_types\n",
+    );
+    csv += &format!(
+      "{},{},{},{},{},{},{},{},{}\n",
+      csv_field(&self.info.title),
+      csv_field(&self.info.song_author),
+      score.score_point,
+      score.achievement_rate,
+      score.accuracy,
+      score
+        .remaining_life
+        .map_or_else(String::new, |life| life.to_string()),
+      score.max_combo,
+      score.correction_type_count(),
+      score.wrong_type_count()
+    );
+    csv
+  }
+
+  pub fn notes_csv(&self) -> String {
+    let mut csv = String::from(
+      "id,section,note,sentence,correct,wrong,outcome\n",
+    );
+    for note in self.log.notes() {
+      csv += &format!(
+        "{},{},{},{},{},{},{}\n",
+        note.id,
+        note.id.section,
+        note.id.note,
+        csv_field(&note.sentence),
+        note.correct,
+        note.wrong,
+        note.outcome.as_str()
+      );
+    }
+    csv
+  }
+
+  pub fn keystrokes_csv(&self) -> String {
+    let mut csv = String::from("time,result,note\n");
+    for keystroke in self.log.keystrokes() {
+      csv += &format!(
+        "{},{},{}\n",
+        keystroke.time.as_f64(),
+        result_str(&keystroke.result),
+        keystroke
+          .note
+          .as_ref()
+          .map_or_else(String::new, NoteId::to_string)
+      );
+    }
+    csv
+  }
+
+  // stem.json と、成績・ノーツ・打鍵ごとの 3 つの CSV を書き出す
+  pub fn write_to(
+    &self,
+    dir: &Path,
+    stem: &str,
+  ) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let files = [
+      (format!("{}.json", stem), self.to_json()),
+      (format!("{}-score.csv", stem), self.score_csv()),
+      (format!("{}-notes.csv", stem), self.notes_csv()),
+      (format!("{}-keystrokes.csv", stem), self.keystrokes_csv()),
+    ];
+    let mut written = vec![];
+    for (name, content) in files {
+      let path = dir.join(name);
+      fs::write(&path, content)?;
+      written.push(path);
+    }
+    Ok(written)
+  }
+}
+
+// 書き出した時刻から、重ならないファイル名を作る
+pub fn default_stem() -> String {
+  let secs = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |elapsed| elapsed.as_secs());
+  format!("result-{}", secs)
+}
+
+fn result_str(result: &MusicalTypeResult) -> &'static str {
+  match result {
+    MusicalTypeResult::Correct => "correct",
+    MusicalTypeResult::Missed => "missed",
+    MusicalTypeResult::Vacant => "vacant",
+  }
+}
+
+fn json_string(text: &str) -> String {
+  let mut escaped = String::from("\"");
+  for c in text.chars() {
+    match c {
+      '"' => escaped += "\\\"",
+      '\\' => escaped += "\\\\",
+      '\n' => escaped += "\\n",
+      '\r' => escaped += "\\r",
+      '\t' => escaped += "\\t",
+      c if c.is_control() => {
+        escaped += &format!("\\u{:04x}", c as u32)
+      }
+      c => escaped.push(c),
+    }
+  }
+  escaped.push('"');
+  escaped
+}
+
+// JSON は NaN や無限大を表せないので null にする
+fn json_number(number: f64) -> String {
+  if number.is_finite() {
+    number.to_string()
+  } else {
+    "null".into()
+  }
+}
+
+fn csv_field(text: &str) -> String {
+  if text.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", text.replace('"', "\"\""))
+  } else {
+    text.into()
+  }
+}
+
+#[test]
+fn escape() {
+  assert_eq!(r#""a\"b\\c\n""#, json_string("a\"b\\c\n"));
+  assert_eq!("null", json_number(f64::NAN));
+  assert_eq!("\"a,\"\"b\"\"\"", csv_field("a,\"b\""));
+  assert_eq!("歌詞", csv_field("歌詞"));
+}
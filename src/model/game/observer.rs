@@ -0,0 +1,15 @@
+use super::MusicalTyperEvent;
+
+// 起きた出来事を、返り値とは別に受け取る
+pub trait MusicalTyperObserver {
+  fn notify(&mut self, event: &MusicalTyperEvent);
+}
+
+impl<F> MusicalTyperObserver for F
+where
+  F: FnMut(&MusicalTyperEvent),
+{
+  fn notify(&mut self, event: &MusicalTyperEvent) {
+    self(event)
+  }
+}
@@ -0,0 +1,177 @@
+use super::{MusicalTypeResult, MusicalTyperEvent};
+use crate::model::exp::{
+  note::NoteId, section::SectionStats, time::Seconds,
+};
+
+#[readonly::make]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keystroke {
+  pub time: Seconds,
+  pub result: MusicalTypeResult,
+  pub note: Option<NoteId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoteOutcome {
+  Completed,
+  Missed,
+  // 打ち終わる前に曲が終わったもの
+  Unfinished,
+}
+
+impl NoteOutcome {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      NoteOutcome::Completed => "completed",
+      NoteOutcome::Missed => "missed",
+      NoteOutcome::Unfinished => "unfinished",
+    }
+  }
+}
+
+// 歌詞のあるノーツ 1 つぶんの結果
+#[readonly::make]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteResult {
+  pub id: NoteId,
+  pub sentence: String,
+  pub correct: u32,
+  pub wrong: u32,
+  pub outcome: NoteOutcome,
+}
+
+// 結果画面で振り返るための、プレイ中の出来事の記録
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayLog {
+  sections: Vec<SectionStats>,
+  missed_sentences: Vec<String>,
+  keystrokes: Vec<Keystroke>,
+  notes: Vec<NoteResult>,
+  current_note: Option<NoteId>,
+  end_time: Seconds,
+}
+
+impl PlayLog {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn record(&mut self, time: Seconds, event: &MusicalTyperEvent) {
+    use MusicalTyperEvent::*;
+    self.end_time = self.end_time.max(time);
+    match event {
+      Typed(MusicalTypeResult::Vacant) => {}
+      Typed(result) => {
+        self.keystrokes.push(Keystroke {
+          time,
+          result: result.clone(),
+          note: self.current_note,
+        });
+        if let Some(note) = self.current_note_result() {
+          match result {
+            MusicalTypeResult::Correct => note.correct += 1,
+            _ => note.wrong += 1,
+          }
+        }
+      }
+      ChangedNote(id) => self.current_note = Some(*id),
+      UpdateSentence(sentence) if !sentence.origin().is_empty() => {
+        if let Some(id) = self.current_note {
+          if self.notes.last().map(|note| note.id) != Some(id) {
+            self.notes.push(NoteResult {
+              id,
+              sentence: sentence.origin().into(),
+              correct: 0,
+              wrong: 0,
+              outcome: NoteOutcome::Unfinished,
+            });
+          }
+        }
+      }
+      CompletedSentence(_) => {
+        if let Some(note) = self.current_note_result() {
+          note.outcome = NoteOutcome::Completed;
+        }
+      }
+      MissedSentence(sentence) if !sentence.origin().is_empty() => {
+        self.missed_sentences.push(sentence.origin().into());
+        // 取り逃しは次のノーツに移ってから届くので、その前のノーツに付ける
+        let current = self.current_note;
+        if let Some(note) = self.notes.iter_mut().rev().find(|note| {
+          Some(note.id) != current
+            && note.outcome == NoteOutcome::Unfinished
+        }) {
+          note.outcome = NoteOutcome::Missed;
+        }
+      }
+      EndedSection(stats) => self.sections.push(stats.clone()),
+      _ => {}
+    }
+  }
+
+  fn current_note_result(&mut self) -> Option<&mut NoteResult> {
+    let current = self.current_note?;
+    self.notes.last_mut().filter(|note| note.id == current)
+  }
+
+  pub fn sections(&self) -> &[SectionStats] {
+    &self.sections
+  }
+
+  pub fn missed_sentences(&self) -> &[String] {
+    &self.missed_sentences
+  }
+
+  pub fn keystrokes(&self) -> &[Keystroke] {
+    &self.keystrokes
+  }
+
+  pub fn notes(&self) -> &[NoteResult] {
+    &self.notes
+  }
+
+  pub fn end_time(&self) -> Seconds {
+    self.end_time
+  }
+
+  // interval ごとに区切った、正しい打鍵の速さ (打/秒)
+  pub fn speed_over_time(&self, interval: Seconds) -> Vec<f64> {
+    let interval = interval.as_f64();
+    let len = (self.end_time.as_f64() / interval).ceil() as usize;
+    if len == 0 {
+      return vec![];
+    }
+    let mut counts = vec![0usize; len];
+    for keystroke in &self.keystrokes {
+      if keystroke.result != MusicalTypeResult::Correct {
+        continue;
+      }
+      let index = (keystroke.time.as_f64() / interval) as usize;
+      counts[index.min(len - 1)] += 1;
+    }
+    counts
+      .into_iter()
+      .map(|count| count as f64 / interval)
+      .collect()
+  }
+
+  // 複数の曲の記録を続けてつなぐ。後の曲の時刻は前の曲の終わりから数える
+  pub fn merge(&self, other: &PlayLog) -> Self {
+    let offset = self.end_time;
+    let mut merged = self.clone();
+    merged.sections.extend(other.sections.iter().cloned());
+    merged
+      .missed_sentences
+      .extend(other.missed_sentences.iter().cloned());
+    merged.keystrokes.extend(other.keystrokes.iter().map(
+      |keystroke| Keystroke {
+        time: keystroke.time + offset,
+        ..keystroke.clone()
+      },
+    ));
+    merged.notes.extend(other.notes.iter().cloned());
+    merged.current_note = other.current_note;
+    merged.end_time = offset + other.end_time;
+    merged
+  }
+}
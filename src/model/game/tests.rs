@@ -1,10 +1,12 @@
 use super::{
   super::exp::{scoremap::Scoremap, sentence::Sentence},
-  MusicalTypeResult, MusicalTyper, MusicalTyperConfig,
-  MusicalTyperError, MusicalTyperEvent,
+  export::ResultExport,
+  ManualClock, MusicalTypeResult, MusicalTyper, MusicalTyperConfig,
+  MusicalTyperError, MusicalTyperEvent, NoteOutcome,
 };
 
 enum Input {
+  Start,
   Wait(f64),
   KeyPress(&'static str),
 }
@@ -32,10 +34,12 @@ fn op1() -> Result<(), MusicalTyperError> {
     |config| config.ignore_unsupported_property(true),
   )?;
 
-  let inputs = &[Wait(2.22), KeyPress("dakentesuto"), Wait(1.0)];
+  let inputs =
+    &[Start, Wait(2.22), KeyPress("dakentesuto"), Wait(1.0)];
   use MusicalTyperEvent::*;
   let expected_events = vec![
     PlayBgm("void.ogg".into()),
+    UpdateSentence(Sentence::empty()),
     UpdateSentence(Sentence::new_with_inputted(
       "打鍵テスト",
       "だけんてすと",
@@ -72,9 +76,22 @@ fn op1() -> Result<(), MusicalTyperError> {
   let actual_events: Vec<_> = inputs
     .iter()
     .flat_map(|input| match input {
+      Start => game.start(),
       Wait(time) => game.set_time((*time).into()),
       KeyPress(key) => game.key_press(key.chars()),
     })
+    // ノーツやセクション、コンボ、点数の変化は event_stream で確かめる
+    .filter(|event| {
+      !matches!(
+        event,
+        ChangedNote(_)
+          | StartedSection(_)
+          | EndedSection(_)
+          | ChangedCaption(_)
+          | ChangedCombo(_)
+          | AdjustedScore(_)
+      )
+    })
     .collect();
 
   for (i, (expected, actual)) in
@@ -88,3 +105,479 @@ fn op1() -> Result<(), MusicalTyperError> {
 
   Ok(())
 }
+
+#[test]
+fn adaptive_practice() -> Result<(), MusicalTyperError> {
+  let test_score = Scoremap::from_str(
+    r#"
+:title TEST
+:bpm 120
+
+[start]
+*1.0
+打鍵テスト
+:だけんてすと
+
+*3.0
+打鍵テスト
+:だけんてすと
+
+*5.0
+[end]
+"#,
+    |config| config.ignore_unsupported_property(true),
+  )?;
+
+  let mut game = MusicalTyper::new(
+    test_score,
+    MusicalTyperConfig::default().adaptive_practice(true),
+  )?;
+  assert!(!game.has_song());
+  let _ = game.start();
+  use MusicalTyperEvent::*;
+  assert!(!game
+    .set_time(1.5.into())
+    .iter()
+    .any(|event| matches!(event, PlayBgm(_))));
+
+  game.observe_type_speed(2.0);
+  let _ = game.set_time(3.0.into());
+  let stretched = game
+    .activity()
+    .current_note()
+    .map(|note| note.duration().length().as_f64())
+    .unwrap();
+  assert!(2.0 < stretched);
+  assert_eq!(
+    "打鍵テスト",
+    game.activity().current_sentence().origin()
+  );
+  Ok(())
+}
+
+#[test]
+fn life_gauge() -> Result<(), MusicalTyperError> {
+  let test_score = || {
+    Scoremap::from_str(
+      r#"
+:title TEST
+:song_data void.ogg
+
+[start]
+*1.0
+打鍵テスト
+:だけんてすと
+
+*3.0
+[end]
+"#,
+      |config| config.ignore_unsupported_property(true),
+    )
+  };
+
+  let mut game = MusicalTyper::new(
+    test_score()?,
+    MusicalTyperConfig::default().life(Some(3)),
+  )?;
+  let _ = game.start();
+  let _ = game.set_time(1.5.into());
+  let _ = game.key_press("xxx".chars());
+  assert_eq!(Some(0), game.activity().score().remaining_life);
+  assert_eq!(
+    Some(&MusicalTyperEvent::EndOfScore),
+    game.set_time(1.6.into()).last()
+  );
+  assert!(game.set_time(1.7.into()).is_empty());
+
+  let mut game =
+    MusicalTyper::new(test_score()?, MusicalTyperConfig::default())?;
+  let _ = game.start();
+  let _ = game.set_time(1.5.into());
+  let _ = game.key_press("dakentesuto".chars());
+  let first = game.activity().score().clone();
+  let mut game =
+    MusicalTyper::new(test_score()?, MusicalTyperConfig::default())?;
+  let _ = game.start();
+  let _ = game.set_time(1.5.into());
+  let _ = game.key_press("dx".chars());
+  let second = game.activity().score().clone();
+
+  let total = first.merge(&second);
+  assert_eq!(
+    first.score_point + second.score_point,
+    total.score_point
+  );
+  assert_eq!(12.0 / 13.0, total.accuracy);
+  assert_eq!(12.0 / 22.0, total.achievement_rate);
+  let unplayed = total.merge_unplayed(&test_score()?.sections);
+  assert_eq!(12.0 / 33.0, unplayed.achievement_rate);
+  assert_eq!(total.accuracy, unplayed.accuracy);
+  Ok(())
+}
+
+#[test]
+fn timestamped_keys() -> Result<(), MusicalTyperError> {
+  let test_score = Scoremap::from_str(
+    r#"
+:title TEST
+:song_data void.ogg
+
+[start]
+*1.0
+えっ
+:えっ
+
+*2.0
+えっ
+:えっ
+
+*3.0
+[end]
+"#,
+    |config| config.ignore_unsupported_property(true),
+  )?;
+
+  let mut game =
+    MusicalTyper::new(test_score, MusicalTyperConfig::default())?;
+  let _ = game.start();
+  let _ = game.set_time(1.5.into());
+  // 2 文目が始まった後に押された打鍵は、まとめて処理されても 2 文目に入る
+  let events = game.key_press_with_time(
+    [
+      ('e', 1.6.into()),
+      ('x', 1.7.into()),
+      ('t', 1.8.into()),
+      ('u', 1.9.into()),
+      ('e', 2.1.into()),
+    ]
+    .into_iter(),
+  );
+  use MusicalTyperEvent::*;
+  assert!(events.iter().any(|event| matches!(
+    event,
+    CompletedSentence(sentence) if sentence.origin() == "えっ"
+  )));
+  assert!(!events
+    .iter()
+    .any(|event| matches!(event, MissedSentence(_))));
+  assert_eq!(2.1, game.current_time());
+  assert_eq!(
+    "e",
+    game.activity().current_sentence().roman().inputted
+  );
+  Ok(())
+}
+
+#[test]
+fn manual_clock() -> Result<(), MusicalTyperError> {
+  let test_score = Scoremap::from_str(
+    r#"
+:title TEST
+:song_data void.ogg
+
+[start]
+*1.0
+えっ
+:えっ
+
+*2.0
+[end]
+"#,
+    |config| config.ignore_unsupported_property(true),
+  )?;
+
+  let mut game =
+    MusicalTyper::new(test_score, MusicalTyperConfig::default())?;
+  let _ = game.start();
+  let clock = ManualClock::new();
+  game.set_clock(Box::new(clock.clone()));
+
+  let _ = game.tick();
+  assert_eq!("", game.activity().current_sentence().origin());
+  clock.set(1.2.into());
+  let _ = game.tick();
+  assert_eq!(1.2, game.current_time());
+  assert_eq!(1.2, game.clock_time());
+  assert_eq!("えっ", game.activity().current_sentence().origin());
+  clock.advance(1.0.into());
+  use MusicalTyperEvent::*;
+  assert!(game
+    .tick()
+    .iter()
+    .any(|event| matches!(event, MissedSentence(_))));
+  Ok(())
+}
+
+#[test]
+fn offset() -> Result<(), MusicalTyperError> {
+  let test_score = Scoremap::from_str(
+    r#"
+:title TEST
+:song_data void.ogg
+:offset 0.5
+
+[start]
+*1.0
+えっ
+:えっ
+
+*2.0
+[end]
+"#,
+    |config| config.ignore_unsupported_property(true),
+  )?;
+
+  let mut game = MusicalTyper::new(
+    test_score,
+    MusicalTyperConfig::default().user_offset(0.2.into()),
+  )?;
+  let _ = game.start();
+  let _ = game.set_time(1.6.into());
+  assert_eq!("", game.activity().current_sentence().origin());
+  let _ = game.set_time(1.8.into());
+  assert_eq!("えっ", game.activity().current_sentence().origin());
+  Ok(())
+}
+
+#[test]
+fn before_start() -> Result<(), MusicalTyperError> {
+  let test_score = Scoremap::from_str(
+    r#"
+:title TEST
+:song_data void.ogg
+
+[start]
+*1.0
+えっ
+:えっ
+
+*2.0
+[end]
+"#,
+    |config| config.ignore_unsupported_property(true),
+  )?;
+
+  let mut game =
+    MusicalTyper::new(test_score, MusicalTyperConfig::default())?;
+  assert!(!game.is_started());
+  assert_eq!("えっ", game.activity().first_sentence().origin());
+  let _ = game.set_time(1.5.into());
+  assert_eq!("", game.activity().current_sentence().origin());
+  use MusicalTyperEvent::*;
+  assert!(game
+    .key_press("e".chars())
+    .contains(&Typed(MusicalTypeResult::Vacant)));
+
+  assert!(game
+    .start()
+    .iter()
+    .any(|event| matches!(event, PlayBgm(_))));
+  assert!(game.is_started());
+  assert!(game.start().is_empty());
+  let _ = game.set_time(1.5.into());
+  assert_eq!("えっ", game.activity().current_sentence().origin());
+  Ok(())
+}
+
+#[test]
+fn event_stream() -> Result<(), MusicalTyperError> {
+  use std::{cell::RefCell, rc::Rc};
+  let test_score = Scoremap::from_str(
+    r#"
+:title TEST
+:song_data void.ogg
+
+[start]
+*1.0
+@A
+>> 前奏
+
+*2.0
+@B
+えっ
+:えっ
+
+*3.0
+えっ
+:えっ
+
+*4.0
+[end]
+"#,
+    |config| config.ignore_unsupported_property(true),
+  )?;
+
+  let mut game =
+    MusicalTyper::new(test_score, MusicalTyperConfig::default())?;
+  let observed = Rc::new(RefCell::new(vec![]));
+  {
+    let observed = Rc::clone(&observed);
+    game.subscribe(move |event: &MusicalTyperEvent| {
+      observed.borrow_mut().push(event.clone())
+    });
+  }
+
+  let mut events = game.start();
+  events.append(&mut game.set_time(1.5.into()));
+  events.append(&mut game.set_time(2.5.into()));
+  events.append(&mut game.key_press("exxtu".chars()));
+  events.append(&mut game.set_time(3.5.into()));
+  events.append(&mut game.set_time(4.5.into()));
+  events.append(&mut game.set_time(100.0.into()));
+  assert_eq!(events, *observed.borrow());
+
+  use MusicalTyperEvent::*;
+  let sections: Vec<_> = events
+    .iter()
+    .filter_map(|event| match event {
+      StartedSection(index) => Some((true, *index)),
+      EndedSection(stats) => Some((false, stats.index)),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(
+    vec![
+      (true, 0),
+      (false, 0),
+      (true, 1),
+      (false, 1),
+      (true, 2),
+      (false, 2)
+    ],
+    sections
+  );
+  assert!(events.contains(&ChangedCaption("前奏".into())));
+  let combos: Vec<_> = events
+    .iter()
+    .filter_map(|event| match event {
+      ChangedCombo(combo) => Some(*combo),
+      _ => None,
+    })
+    .collect();
+  // 2 文目を取り逃したので、コンボが途切れる
+  assert_eq!(vec![1, 2, 0, 1, 2, 0], combos);
+  assert_eq!(2, game.activity().score().max_combo);
+  assert!(events.contains(&AdjustedScore(-30)));
+  assert!(events.contains(&AdjustedScore(-2)));
+  if let Some(EndedSection(stats)) = events
+    .iter()
+    .rev()
+    .find(|event| matches!(event, EndedSection(_)))
+  {
+    assert_eq!((1, 2), (stats.completed_sentences, stats.sentences));
+  }
+  assert_eq!(EndOfScore, *events.last().unwrap());
+
+  let log = game.play_log();
+  assert_eq!(3, log.sections().len());
+  assert_eq!(["えっ"], log.missed_sentences());
+  assert_eq!(5, log.keystrokes().len());
+  assert_eq!(
+    game.activity().score().wrong_type_count(),
+    log
+      .keystrokes()
+      .iter()
+      .filter(
+        |keystroke| keystroke.result == MusicalTypeResult::Missed
+      )
+      .count() as u32
+  );
+  let speed = log.speed_over_time(1.0.into());
+  assert_eq!(100, speed.len());
+  assert_eq!(4.0, speed[2]);
+  let merged = log.merge(log);
+  assert_eq!(200.0, merged.end_time());
+  assert_eq!(102.5, merged.keystrokes()[5].time);
+  let outcomes: Vec<_> = log
+    .notes()
+    .iter()
+    .map(|note| (note.correct, note.wrong, note.outcome))
+    .collect();
+  assert_eq!(
+    vec![(4, 1, NoteOutcome::Completed), (0, 0, NoteOutcome::Missed)],
+    outcomes
+  );
+  let first_note = log.notes().first().map(|note| note.id);
+  assert!(log
+    .keystrokes()
+    .iter()
+    .all(|keystroke| keystroke.note == first_note));
+
+  let info = game.music_info();
+  let score = game.activity().score();
+  let export = ResultExport::new(&info, score, log);
+  let notes_csv = export.notes_csv();
+  assert_eq!(3, notes_csv.lines().count());
+  assert!(notes_csv
+    .lines()
+    .nth(2)
+    .unwrap()
+    .ends_with(",えっ,0,0,missed"));
+  assert_eq!(6, export.keystrokes_csv().lines().count());
+  let json = export.to_json();
+  assert!(json.contains("\"title\": \"TEST\""));
+  assert!(json.contains("\"outcome\": \"missed\""));
+  Ok(())
+}
+
+#[test]
+fn upcoming_sentences() -> Result<(), MusicalTyperError> {
+  let test_score = Scoremap::from_str(
+    r#"
+:title TEST
+:song_data void.ogg
+
+[start]
+*1.0
+えっ
+:えっ
+
+*2.0
+間奏
+
+*3.0
+おっ
+:おっ
+
+*4.0
+あっ
+:あっ
+
+*5.0
+[end]
+"#,
+    |config| config.ignore_unsupported_property(true),
+  )?;
+
+  let mut game =
+    MusicalTyper::new(test_score, MusicalTyperConfig::default())?;
+  let _ = game.start();
+  let _ = game.set_time(0.5.into());
+  let upcoming: Vec<_> = game
+    .upcoming_sentences(2)
+    .into_iter()
+    .map(|(until, sentence)| (until, sentence.origin().to_string()))
+    .collect();
+  assert_eq!(
+    vec![
+      (0.5.into(), "えっ".to_string()),
+      (2.5.into(), "おっ".into())
+    ],
+    upcoming
+  );
+
+  let _ = game.set_time(1.5.into());
+  let upcoming: Vec<_> = game
+    .upcoming_sentences(2)
+    .into_iter()
+    .map(|(until, sentence)| (until, sentence.origin().to_string()))
+    .collect();
+  assert_eq!(
+    vec![
+      (1.5.into(), "おっ".to_string()),
+      (2.5.into(), "あっ".into())
+    ],
+    upcoming
+  );
+  Ok(())
+}
@@ -0,0 +1,241 @@
+use std::{
+  fmt::{Display, Formatter},
+  str::FromStr,
+};
+
+#[derive(Debug)]
+pub enum KeyboardLayoutError {
+  UnknownName { name: String },
+}
+
+// 画面のキーボードの配列。打った文字は OS の配列で決まるので、同じものを選ぶ
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyboardLayout {
+  Jis,
+  Us,
+  Dvorak,
+  Colemak,
+}
+
+// キーボードの 1 列。位置は配列によらない物理的なキーを、JIS 配列での刻印で表す
+pub struct KeyRow {
+  // 左端のずれ。キーの幅を 1 とする
+  pub offset: f64,
+  pub positions: &'static str,
+  pub legends: &'static str,
+}
+
+const JIS_ROWS: [KeyRow; 4] = [
+  KeyRow {
+    offset: 0.0,
+    positions: "1234567890-^¥",
+    legends: "1234567890-^¥",
+  },
+  KeyRow {
+    offset: 0.5,
+    positions: "qwertyuiop@[",
+    legends: "qwertyuiop@[",
+  },
+  KeyRow {
+    offset: 0.75,
+    positions: "asdfghjkl;:]",
+    legends: "asdfghjkl;:]",
+  },
+  KeyRow {
+    offset: 1.25,
+    positions: "zxcvbnm,./\\",
+    legends: "zxcvbnm,./\\",
+  },
+];
+
+// US 配列ではバックスラッシュが上の段の右端にあり、JIS の ] と同じキーになる
+const US_POSITIONS: [&str; 4] =
+  ["1234567890-^", "qwertyuiop@[]", "asdfghjkl;:", "zxcvbnm,./"];
+
+const US_ROWS: [KeyRow; 4] = us_rows([
+  "1234567890-=",
+  "qwertyuiop[]\\",
+  "asdfghjkl;'",
+  "zxcvbnm,./",
+]);
+
+const DVORAK_ROWS: [KeyRow; 4] = us_rows([
+  "1234567890[]",
+  "',.pyfgcrl/=\\",
+  "aoeuidhtns-",
+  ";qjkxbmwvz",
+]);
+
+const COLEMAK_ROWS: [KeyRow; 4] = us_rows([
+  "1234567890-=",
+  "qwfpgjluy;[]\\",
+  "arstdhneio'",
+  "zxcvbkm,./",
+]);
+
+const fn us_rows(legends: [&'static str; 4]) -> [KeyRow; 4] {
+  [
+    KeyRow {
+      offset: 0.0,
+      positions: US_POSITIONS[0],
+      legends: legends[0],
+    },
+    KeyRow {
+      offset: 0.5,
+      positions: US_POSITIONS[1],
+      legends: legends[1],
+    },
+    KeyRow {
+      offset: 0.75,
+      positions: US_POSITIONS[2],
+      legends: legends[2],
+    },
+    KeyRow {
+      offset: 1.25,
+      positions: US_POSITIONS[3],
+      legends: legends[3],
+    },
+  ]
+}
+
+impl KeyboardLayout {
+  pub const ALL: [KeyboardLayout; 4] = [
+    KeyboardLayout::Jis,
+    KeyboardLayout::Us,
+    KeyboardLayout::Dvorak,
+    KeyboardLayout::Colemak,
+  ];
+
+  pub fn title(&self) -> &'static str {
+    match self {
+      KeyboardLayout::Jis => "JIS",
+      KeyboardLayout::Us => "US",
+      KeyboardLayout::Dvorak => "Dvorak",
+      KeyboardLayout::Colemak => "Colemak",
+    }
+  }
+
+  pub fn rows(&self) -> &'static [KeyRow; 4] {
+    match self {
+      KeyboardLayout::Jis => &JIS_ROWS,
+      KeyboardLayout::Us => &US_ROWS,
+      KeyboardLayout::Dvorak => &DVORAK_ROWS,
+      KeyboardLayout::Colemak => &COLEMAK_ROWS,
+    }
+  }
+
+  // キーの位置と OS がそのキーに割り当てた文字の組から、
+  // いちばん多く合う配列を選ぶ。同じ数なら ALL の前にあるものにする
+  pub fn detect(legends: &[(char, char)]) -> Option<KeyboardLayout> {
+    KeyboardLayout::ALL
+      .iter()
+      .rev()
+      .map(|&layout| {
+        let matched = legends
+          .iter()
+          .filter(|&&(position, legend)| {
+            layout.legend_at(position) == Some(legend)
+          })
+          .count();
+        (layout, matched)
+      })
+      .filter(|&(_, matched)| 0 < matched)
+      .max_by_key(|&(_, matched)| matched)
+      .map(|(layout, _)| layout)
+  }
+
+  // 物理的なキーの位置に、この配列で刻まれている文字
+  pub fn legend_at(&self, position: char) -> Option<char> {
+    self.rows().iter().find_map(|row| {
+      row
+        .positions
+        .chars()
+        .zip(row.legends.chars())
+        .find(|&(p, _)| p == position)
+        .map(|(_, legend)| legend)
+    })
+  }
+}
+
+impl FromStr for KeyboardLayout {
+  type Err = KeyboardLayoutError;
+
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    match name {
+      "jis" => Ok(KeyboardLayout::Jis),
+      "us" => Ok(KeyboardLayout::Us),
+      "dvorak" => Ok(KeyboardLayout::Dvorak),
+      "colemak" => Ok(KeyboardLayout::Colemak),
+      _ => {
+        Err(KeyboardLayoutError::UnknownName { name: name.into() })
+      }
+    }
+  }
+}
+
+impl Display for KeyboardLayout {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      KeyboardLayout::Jis => "jis",
+      KeyboardLayout::Us => "us",
+      KeyboardLayout::Dvorak => "dvorak",
+      KeyboardLayout::Colemak => "colemak",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Finger {
+  Index,
+  Middle,
+  Ring,
+  Little,
+}
+
+impl Finger {
+  // 列の左から何番目かで、打つ指を決める。
+  // 人差し指は左右とも 2 列ずつ受け持ち、右の小指は残りの列をすべて受け持つ
+  pub fn of_column(column: usize) -> Finger {
+    match column {
+      0 | 9.. => Finger::Little,
+      1 | 8 => Finger::Ring,
+      2 | 7 => Finger::Middle,
+      _ => Finger::Index,
+    }
+  }
+}
+
+#[test]
+fn keyboard_layout() -> Result<(), KeyboardLayoutError> {
+  for layout in KeyboardLayout::ALL {
+    for row in layout.rows() {
+      assert_eq!(
+        row.positions.chars().count(),
+        row.legends.chars().count()
+      );
+    }
+    assert_eq!(layout, layout.to_string().parse()?);
+  }
+  assert_eq!(Some('s'), KeyboardLayout::Jis.legend_at('s'));
+  assert_eq!(Some('o'), KeyboardLayout::Dvorak.legend_at('s'));
+  assert_eq!(Some('r'), KeyboardLayout::Colemak.legend_at('s'));
+  assert_eq!(Some('\''), KeyboardLayout::Us.legend_at(':'));
+  assert_eq!(None, KeyboardLayout::Us.legend_at('¥'));
+  for layout in KeyboardLayout::ALL {
+    let legends: Vec<_> = layout
+      .rows()
+      .iter()
+      .flat_map(|row| row.positions.chars().zip(row.legends.chars()))
+      .collect();
+    assert_eq!(Some(layout), KeyboardLayout::detect(&legends));
+  }
+  assert_eq!(None, KeyboardLayout::detect(&[]));
+  assert_eq!(Finger::Index, Finger::of_column(5));
+  assert_eq!(Finger::Little, Finger::of_column(11));
+  assert!(matches!(
+    "azerty".parse::<KeyboardLayout>(),
+    Err(KeyboardLayoutError::UnknownName { .. })
+  ));
+  Ok(())
+}
@@ -0,0 +1,239 @@
+use crate::model::{
+  exp::{
+    sentence::roman::{RomanBaseStyle, RomanPreferenceError},
+    time::Seconds,
+  },
+  keyboard_layout::KeyboardLayout,
+  skin::{DisplayMode, DEFAULT_SKIN},
+};
+use std::{
+  fmt::{Display, Formatter},
+  fs, io,
+  path::Path,
+  str::FromStr,
+};
+
+// 設定画面で変えた内容を保存しておくファイル
+pub const SETTINGS_PATH: &str = "settings.txt";
+
+pub const WINDOW_SIZES: [(u32, u32); 3] =
+  [(800, 600), (1024, 768), (1280, 960)];
+
+#[derive(Debug)]
+pub enum SettingsError {
+  InvalidLine { line_num: usize },
+  InvalidValue { key: String, value: String },
+  RomanPreference(RomanPreferenceError),
+}
+
+impl From<RomanPreferenceError> for SettingsError {
+  fn from(err: RomanPreferenceError) -> Self {
+    SettingsError::RomanPreference(err)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+  // 0 から 100 までの百分率
+  pub volume: u32,
+  pub roman_style: RomanBaseStyle,
+  // skins ディレクトリの下にあるスキンの名前
+  pub skin: String,
+  pub display_mode: DisplayMode,
+  pub user_offset: Seconds,
+  pub window_size: (u32, u32),
+  pub fullscreen: bool,
+  pub countdown: bool,
+  // 次の仮名の綴りの候補を並べ、打てるキーをすべて光らせる
+  pub spelling_hints: bool,
+  pub keyboard_layout: KeyboardLayout,
+  // 案内のキーを、打つ指の色で塗る
+  pub finger_guide: bool,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      volume: 88,
+      roman_style: RomanBaseStyle::Default,
+      skin: DEFAULT_SKIN.into(),
+      display_mode: DisplayMode::Standard,
+      user_offset: Seconds::default(),
+      window_size: WINDOW_SIZES[0],
+      fullscreen: false,
+      countdown: true,
+      spelling_hints: false,
+      keyboard_layout: KeyboardLayout::Jis,
+      finger_guide: false,
+    }
+  }
+}
+
+impl Settings {
+  // ファイルがなければ既定の設定にする
+  pub fn load(path: &Path) -> Result<Self, SettingsError> {
+    match fs::read_to_string(path) {
+      Ok(text) => text.parse(),
+      Err(_) => Ok(Self::default()),
+    }
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    fs::write(path, self.to_string())
+  }
+}
+
+// `volume 80` のように、1 行に 1 つずつ項目名と値を書く
+impl FromStr for Settings {
+  type Err = SettingsError;
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let mut settings = Settings::default();
+    for (line_num, line) in text.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let mut words = line.split_whitespace();
+      let (key, value) =
+        match (words.next(), words.next(), words.next()) {
+          (Some(key), Some(value), None) => (key, value),
+          _ => {
+            return Err(SettingsError::InvalidLine {
+              line_num: line_num + 1,
+            })
+          }
+        };
+      let invalid = || SettingsError::InvalidValue {
+        key: key.into(),
+        value: value.into(),
+      };
+      match key {
+        "volume" => {
+          settings.volume = value
+            .parse::<u32>()
+            .ok()
+            .filter(|volume| *volume <= 100)
+            .ok_or_else(invalid)?;
+        }
+        "roman" => settings.roman_style = value.parse()?,
+        "skin" => settings.skin = value.into(),
+        "display" => {
+          settings.display_mode =
+            value.parse().map_err(|_| invalid())?
+        }
+        "offset" => {
+          settings.user_offset =
+            value.parse::<f64>().map_err(|_| invalid())?.into();
+        }
+        "window_size" => {
+          settings.window_size = value
+            .split_once('x')
+            .and_then(|(width, height)| {
+              Some((width.parse().ok()?, height.parse().ok()?))
+            })
+            .ok_or_else(invalid)?;
+        }
+        "fullscreen" => {
+          settings.fullscreen =
+            parse_switch(value).ok_or_else(invalid)?
+        }
+        "countdown" => {
+          settings.countdown =
+            parse_switch(value).ok_or_else(invalid)?
+        }
+        "hints" => {
+          settings.spelling_hints =
+            parse_switch(value).ok_or_else(invalid)?
+        }
+        "keyboard" => {
+          settings.keyboard_layout =
+            value.parse().map_err(|_| invalid())?
+        }
+        "fingers" => {
+          settings.finger_guide =
+            parse_switch(value).ok_or_else(invalid)?
+        }
+        _ => return Err(invalid()),
+      }
+    }
+    Ok(settings)
+  }
+}
+
+impl Display for Settings {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "volume {}", self.volume)?;
+    writeln!(f, "roman {}", self.roman_style)?;
+    writeln!(f, "skin {}", self.skin)?;
+    writeln!(f, "display {}", self.display_mode)?;
+    writeln!(f, "offset {}", self.user_offset.as_f64())?;
+    writeln!(
+      f,
+      "window_size {}x{}",
+      self.window_size.0, self.window_size.1
+    )?;
+    writeln!(f, "fullscreen {}", switch_str(self.fullscreen))?;
+    writeln!(f, "countdown {}", switch_str(self.countdown))?;
+    writeln!(f, "hints {}", switch_str(self.spelling_hints))?;
+    writeln!(f, "keyboard {}", self.keyboard_layout)?;
+    writeln!(f, "fingers {}", switch_str(self.finger_guide))
+  }
+}
+
+fn parse_switch(value: &str) -> Option<bool> {
+  match value {
+    "on" => Some(true),
+    "off" => Some(false),
+    _ => None,
+  }
+}
+
+fn switch_str(on: bool) -> &'static str {
+  if on {
+    "on"
+  } else {
+    "off"
+  }
+}
+
+#[test]
+fn settings() -> Result<(), SettingsError> {
+  let settings: Settings = r#"
+# 音量は控えめに
+volume 40
+roman kunrei
+skin night
+display colorblind
+offset -0.05
+window_size 1024x768
+fullscreen on
+countdown off
+hints on
+keyboard dvorak
+fingers on
+"#
+  .parse()?;
+  assert_eq!(40, settings.volume);
+  assert_eq!(RomanBaseStyle::Kunrei, settings.roman_style);
+  assert_eq!("night", settings.skin);
+  assert_eq!(DisplayMode::Colorblind, settings.display_mode);
+  assert_eq!(-0.05, settings.user_offset);
+  assert_eq!((1024, 768), settings.window_size);
+  assert!(settings.fullscreen);
+  assert!(!settings.countdown);
+  assert!(settings.spelling_hints);
+  assert_eq!(KeyboardLayout::Dvorak, settings.keyboard_layout);
+  assert!(settings.finger_guide);
+  assert_eq!(settings, settings.to_string().parse()?);
+
+  assert!(matches!(
+    "volume 120".parse::<Settings>(),
+    Err(SettingsError::InvalidValue { .. })
+  ));
+  assert!(matches!(
+    "volume".parse::<Settings>(),
+    Err(SettingsError::InvalidLine { line_num: 1 })
+  ));
+  Ok(())
+}
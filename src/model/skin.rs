@@ -0,0 +1,390 @@
+use std::{
+  collections::BTreeMap,
+  fmt::{Display, Formatter},
+  fs, io,
+  path::{Path, PathBuf},
+  str::FromStr,
+};
+
+// スキンはこのディレクトリの下に、1 つずつディレクトリを作って置く
+pub const SKINS_DIR: &str = "skins";
+// スキンのディレクトリに置く目録のファイル
+pub const MANIFEST_NAME: &str = "skin.txt";
+// 目録がなくても使える、組み込みのスキンの名前
+pub const DEFAULT_SKIN: &str = "default";
+
+// 効果音の名前。目録では `se.correct correct.wav` のように書く
+pub const SOUND_NAMES: [&str; 8] = [
+  "correct",
+  "fail",
+  "vacant",
+  "gameover",
+  "missed",
+  "perfect_sentence",
+  "perfect_section",
+  "click",
+];
+
+#[derive(Debug)]
+pub enum SkinError {
+  Io(io::Error),
+  InvalidLine { line_num: usize },
+  InvalidValue { key: String, value: String },
+}
+
+impl From<io::Error> for SkinError {
+  fn from(err: io::Error) -> Self {
+    SkinError::Io(err)
+  }
+}
+
+// 色はすべて 0xRRGGBB で持つ
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+  pub background: u32,
+  pub text: u32,
+  pub sub_text: u32,
+  pub label: u32,
+  pub accent: u32,
+  pub highlight: u32,
+  pub good: u32,
+  pub warning: u32,
+  pub danger: u32,
+  pub calm: u32,
+  pub border: u32,
+  pub hover: u32,
+  pub lane: u32,
+  pub lane_remaining: u32,
+  pub key_back: u32,
+  pub key_highlight: u32,
+  pub key_pressed: u32,
+  pub key_home: u32,
+  pub key_legend: u32,
+  // 色だけに頼らず、太字や下線、印でも状態を見分けられるようにする
+  pub markers: bool,
+}
+
+impl Default for Palette {
+  fn default() -> Self {
+    Self {
+      background: 0xfdf3e2,
+      text: 0x000000,
+      sub_text: 0x505050,
+      label: 0xa0a0a5,
+      accent: 0x404fb5,
+      highlight: 0x248dff,
+      good: 0x72b566,
+      warning: 0xe7b05f,
+      danger: 0xfa786e,
+      calm: 0xb2ff59,
+      border: 0x0a0d0a,
+      hover: 0xdce0dc,
+      lane: 0xe6dcc8,
+      lane_remaining: 0xcbc1b0,
+      key_back: 0xfdf3e2,
+      key_highlight: 0x144c40,
+      key_pressed: 0xd19a1d,
+      key_home: 0x4050b4,
+      key_legend: 0xc3c3be,
+      markers: false,
+    }
+  }
+}
+
+impl Palette {
+  // スキンの色を、表示モードに合わせて置き換える
+  pub fn with_mode(self, mode: DisplayMode) -> Self {
+    match mode {
+      DisplayMode::Standard => self,
+      // 色覚の型によらず見分けやすい Okabe-Ito の配色にする
+      DisplayMode::Colorblind => Self {
+        accent: 0x0072b2,
+        highlight: 0x0072b2,
+        good: 0x009e73,
+        warning: 0xe69f00,
+        danger: 0xd55e00,
+        calm: 0x56b4e9,
+        key_highlight: 0x0072b2,
+        key_pressed: 0xe69f00,
+        key_home: 0xcc79a7,
+        key_legend: 0xffffff,
+        markers: true,
+        ..self
+      },
+      DisplayMode::HighContrast => Self {
+        background: 0x000000,
+        text: 0xffffff,
+        sub_text: 0xc0c0c0,
+        label: 0xc0c0c0,
+        accent: 0x00ffff,
+        highlight: 0xffff00,
+        good: 0x00ff00,
+        warning: 0xffff00,
+        danger: 0xff5050,
+        calm: 0x00ff00,
+        border: 0xffffff,
+        hover: 0x404040,
+        lane: 0x202020,
+        lane_remaining: 0x505050,
+        key_back: 0x000000,
+        key_highlight: 0xffff00,
+        key_pressed: 0x00ffff,
+        key_home: 0xff80ff,
+        key_legend: 0x808080,
+        markers: true,
+      },
+    }
+  }
+
+  fn color_mut(&mut self, name: &str) -> Option<&mut u32> {
+    let color = match name {
+      "background" => &mut self.background,
+      "text" => &mut self.text,
+      "sub_text" => &mut self.sub_text,
+      "label" => &mut self.label,
+      "accent" => &mut self.accent,
+      "highlight" => &mut self.highlight,
+      "good" => &mut self.good,
+      "warning" => &mut self.warning,
+      "danger" => &mut self.danger,
+      "calm" => &mut self.calm,
+      "border" => &mut self.border,
+      "hover" => &mut self.hover,
+      "lane" => &mut self.lane,
+      "lane_remaining" => &mut self.lane_remaining,
+      "key_back" => &mut self.key_back,
+      "key_highlight" => &mut self.key_highlight,
+      "key_pressed" => &mut self.key_pressed,
+      "key_home" => &mut self.key_home,
+      "key_legend" => &mut self.key_legend,
+      _ => return None,
+    };
+    Some(color)
+  }
+}
+
+// 色の見え方に合わせて選ぶ表示モード
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayMode {
+  Standard,
+  Colorblind,
+  HighContrast,
+}
+
+impl DisplayMode {
+  pub const ALL: [DisplayMode; 3] = [
+    DisplayMode::Standard,
+    DisplayMode::Colorblind,
+    DisplayMode::HighContrast,
+  ];
+
+  pub fn title(&self) -> &'static str {
+    match self {
+      DisplayMode::Standard => "標準",
+      DisplayMode::Colorblind => "色覚配慮",
+      DisplayMode::HighContrast => "高コントラスト",
+    }
+  }
+}
+
+impl FromStr for DisplayMode {
+  type Err = SkinError;
+
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    match name {
+      "standard" => Ok(DisplayMode::Standard),
+      "colorblind" => Ok(DisplayMode::Colorblind),
+      "high_contrast" => Ok(DisplayMode::HighContrast),
+      _ => Err(SkinError::InvalidValue {
+        key: "display".into(),
+        value: name.into(),
+      }),
+    }
+  }
+}
+
+impl Display for DisplayMode {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      DisplayMode::Standard => "standard",
+      DisplayMode::Colorblind => "colorblind",
+      DisplayMode::HighContrast => "high_contrast",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skin {
+  // 設定画面に出す名前
+  pub title: String,
+  pub palette: Palette,
+  pub font: PathBuf,
+  // 効果音の名前から、音声ファイルへのパス
+  pub sounds: BTreeMap<String, PathBuf>,
+}
+
+impl Default for Skin {
+  fn default() -> Self {
+    let asset = Path::new("asset");
+    let sounds = SOUND_NAMES
+      .iter()
+      .map(|&name| {
+        // クリック音は専用の音がないので、空打ちの音で代わりにする
+        let file = if name == "click" { "vacant" } else { name };
+        (name.to_string(), asset.join(format!("{}.wav", file)))
+      })
+      .collect();
+    Self {
+      title: "標準".into(),
+      palette: Palette::default(),
+      font: asset.join("mplus-1m-medium.ttf"),
+      sounds,
+    }
+  }
+}
+
+impl Skin {
+  // `name` のスキンを skins_dir から読み込む。目録に書かれていない項目は
+  // 組み込みのスキンのものを使う
+  pub fn load(
+    skins_dir: &Path,
+    name: &str,
+  ) -> Result<Self, SkinError> {
+    if name == DEFAULT_SKIN {
+      return Ok(Self::default());
+    }
+    let dir = skins_dir.join(name);
+    let text = fs::read_to_string(dir.join(MANIFEST_NAME))?;
+    Self::parse(&text, &dir)
+  }
+
+  // 目録の中のファイルのパスは、スキンのディレクトリからの相対パスで書く
+  pub fn parse(text: &str, dir: &Path) -> Result<Self, SkinError> {
+    let mut skin = Skin::default();
+    for (line_num, line) in text.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let (key, value) = line
+        .split_once(char::is_whitespace)
+        .map(|(key, value)| (key, value.trim()))
+        .ok_or(SkinError::InvalidLine {
+          line_num: line_num + 1,
+        })?;
+      let invalid = || SkinError::InvalidValue {
+        key: key.into(),
+        value: value.into(),
+      };
+      if let Some(name) = key.strip_prefix("color.") {
+        let color =
+          skin.palette.color_mut(name).ok_or_else(invalid)?;
+        *color = parse_color(value).ok_or_else(invalid)?;
+      } else if let Some(name) = key.strip_prefix("se.") {
+        if !SOUND_NAMES.contains(&name) {
+          return Err(invalid());
+        }
+        skin.sounds.insert(name.into(), dir.join(value));
+      } else {
+        match key {
+          "title" => skin.title = value.into(),
+          "font" => skin.font = dir.join(value),
+          _ => return Err(invalid()),
+        }
+      }
+    }
+    Ok(skin)
+  }
+}
+
+// `fdf3e2` や `#fdf3e2` のような 16 進数の色
+fn parse_color(value: &str) -> Option<u32> {
+  let hex = value.strip_prefix('#').unwrap_or(value);
+  if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+    return None;
+  }
+  u32::from_str_radix(hex, 16).ok()
+}
+
+// 選べるスキンの名前を、組み込みのスキンを先頭にして並べる
+pub fn skin_names(skins_dir: &Path) -> Vec<String> {
+  let mut names: Vec<_> = fs::read_dir(skins_dir)
+    .map(|entries| {
+      entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join(MANIFEST_NAME).is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name != DEFAULT_SKIN)
+        .collect()
+    })
+    .unwrap_or_default();
+  names.sort();
+  names.insert(0, DEFAULT_SKIN.into());
+  names
+}
+
+impl FromStr for Skin {
+  type Err = SkinError;
+
+  // パスはカレントディレクトリからの相対パスとして読む
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    Self::parse(text, Path::new(""))
+  }
+}
+
+#[test]
+fn skin() -> Result<(), SkinError> {
+  let skin = Skin::parse(
+    r#"
+# 夜向けの暗い色
+title 夜
+font fonts/night.ttf
+color.background #101018
+color.text e0e0e0
+se.correct sounds/pop.wav
+"#,
+    Path::new("skins/night"),
+  )?;
+  assert_eq!("夜", skin.title);
+  assert_eq!(Path::new("skins/night/fonts/night.ttf"), skin.font);
+  assert_eq!(0x101018, skin.palette.background);
+  assert_eq!(0xe0e0e0, skin.palette.text);
+  assert_eq!(Palette::default().accent, skin.palette.accent);
+  assert_eq!(
+    Path::new("skins/night/sounds/pop.wav"),
+    skin.sounds["correct"]
+  );
+  assert_eq!(Path::new("asset/vacant.wav"), skin.sounds["click"]);
+
+  let colorblind = skin.palette.with_mode(DisplayMode::Colorblind);
+  assert!(colorblind.markers);
+  assert_eq!(0x101018, colorblind.background);
+  assert_ne!(colorblind.key_highlight, colorblind.key_pressed);
+  assert_eq!(
+    skin.palette,
+    skin.palette.with_mode(DisplayMode::Standard)
+  );
+  assert_eq!(
+    DisplayMode::HighContrast,
+    DisplayMode::HighContrast.to_string().parse()?
+  );
+
+  assert!(matches!(
+    "color.unknown 000000".parse::<Skin>(),
+    Err(SkinError::InvalidValue { .. })
+  ));
+  assert!(matches!(
+    "color.text 00000g".parse::<Skin>(),
+    Err(SkinError::InvalidValue { .. })
+  ));
+  assert!(matches!(
+    "se.explosion boom.wav".parse::<Skin>(),
+    Err(SkinError::InvalidValue { .. })
+  ));
+  assert!(matches!(
+    "title".parse::<Skin>(),
+    Err(SkinError::InvalidLine { line_num: 1 })
+  ));
+  Ok(())
+}
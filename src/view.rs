@@ -1,10 +1,22 @@
-use std::rc::Rc;
+use std::{
+  collections::VecDeque,
+  path::{Path, PathBuf},
+  rc::Rc,
+};
 
 use crate::model::exp::{
   game_activity::GameScore,
   scoremap::{MusicInfo, Scoremap},
+  sentence::InputMethod,
+  time::Seconds,
+};
+use crate::model::game::{
+  export::{default_stem, ResultExport, DEFAULT_EXPORT_DIR},
+  MusicalTyperConfig, MusicalTyperError, PlayLog,
 };
-use crate::model::game::MusicalTyperError;
+use crate::model::settings::{Settings, SETTINGS_PATH};
+use crate::model::skin::{Skin, SKINS_DIR};
+use calibration_view::CalibrationView;
 use game_view::GameView;
 use player::PlayerError;
 use result_view::ResultView;
@@ -14,15 +26,32 @@ use rich_sdl2_rust::mixer::{
 };
 use rich_sdl2_rust::ttf::{font::Font, Ttf};
 use rich_sdl2_rust::{
+  color::Rgba,
   renderer::{pen::Pen, Renderer},
-  window::{WindowBuilder, WindowContextKind},
+  window::{WindowBuilder, WindowContextKind, WindowFormat},
   Sdl, Video,
 };
+use settings_view::SettingsView;
+use title_view::TitleView;
 
+mod calibration_view;
 mod components;
 mod game_view;
+mod layout;
 mod player;
 mod result_view;
+mod settings_view;
+mod title_view;
+
+// 0xRRGGBB の色を、不透明な文字の色にする
+fn opaque(color: u32) -> Rgba {
+  Rgba {
+    r: (color >> 16) as u8,
+    g: (color >> 8) as u8,
+    b: color as u8,
+    a: 255,
+  }
+}
 
 pub trait Component {
   type Props;
@@ -56,19 +85,193 @@ pub trait View {
 #[allow(dead_code)]
 pub enum ViewRoute {
   SelectMusic,
+  Play,
+  Practice,
   Start(Scoremap),
   Retry,
-  ResultView(GameScore, MusicInfo),
+  ResultView(GameScore, MusicInfo, PlayLog),
+  Calibrated(Option<Seconds>),
+  Settings,
+  SavedSettings(Settings),
+  // ひとつ前の画面に戻る
+  Back,
   Quit,
 }
 
+// 戻ったときに開き直せる画面
+#[derive(Clone)]
+enum Screen {
+  Title,
+  Settings,
+  Result(GameScore, MusicInfo, PlayLog),
+}
+
 impl From<PlayerError> for ViewError {
   fn from(err: PlayerError) -> Self {
     ViewError::Player(err)
   }
 }
 
+pub struct SetlistSong {
+  pub score: Scoremap,
+  pub practice: bool,
+}
+
+pub struct Setlist {
+  pub title: Option<String>,
+  pub life: Option<u32>,
+  pub songs: Vec<SetlistSong>,
+  pub input_method: InputMethod,
+  pub calibrate: bool,
+  // 指定されていれば、最後の結果を自動で書き出す
+  pub export_dir: Option<PathBuf>,
+}
+
+struct SetlistProgress<'setlist> {
+  setlist: &'setlist Setlist,
+  next_index: usize,
+  total: Option<GameScore>,
+  log: PlayLog,
+  settings: Settings,
+  // settings.skin から読み込んだスキン
+  skin: Skin,
+  // タイトル画面で練習を選んだときは、すべての曲を練習モードにする
+  practice: bool,
+}
+
+impl<'setlist> SetlistProgress<'setlist> {
+  fn new(setlist: &'setlist Setlist, settings: Settings) -> Self {
+    Self {
+      setlist,
+      next_index: 0,
+      total: None,
+      log: PlayLog::new(),
+      skin: load_skin(&settings),
+      settings,
+      practice: false,
+    }
+  }
+
+  fn set_settings(&mut self, settings: Settings) {
+    if settings.skin != self.settings.skin
+      || settings.display_mode != self.settings.display_mode
+    {
+      self.skin = load_skin(&settings);
+    }
+    self.settings = settings;
+  }
+
+  fn save_settings(&self) {
+    if let Err(err) = self.settings.save(Path::new(SETTINGS_PATH)) {
+      println!("設定を保存できませんでした: {}", err);
+    }
+  }
+
+  fn restart(&mut self) {
+    self.next_index = 0;
+    self.total = None;
+    self.log = PlayLog::new();
+  }
+
+  fn next_score(&mut self) -> Option<Scoremap> {
+    let life_ran_out = self
+      .total
+      .as_ref()
+      .map_or(false, |total| total.remaining_life == Some(0));
+    if life_ran_out {
+      return None;
+    }
+    let song = self.setlist.songs.get(self.next_index)?;
+    self.next_index += 1;
+    Some(song.score.clone())
+  }
+
+  fn current_config(&self) -> MusicalTyperConfig {
+    let practice = self
+      .setlist
+      .songs
+      .get(self.next_index.saturating_sub(1))
+      .map_or(false, |song| song.practice)
+      || self.practice;
+    // ライフは曲をまたいで引き継ぐ
+    let life = self
+      .total
+      .as_ref()
+      .map_or(self.setlist.life, |total| total.remaining_life);
+    MusicalTyperConfig::default()
+      .adaptive_practice(practice)
+      .life(life)
+      .input_method(self.setlist.input_method)
+      .user_offset(self.settings.user_offset)
+  }
+
+  fn record(&mut self, score: GameScore, log: &PlayLog) {
+    let mut total = match self.total.take() {
+      Some(total) => total.merge(&score),
+      None => score,
+    };
+    // ライフが尽きて遊べなかった曲も、通しの達成率に数える
+    if total.remaining_life == Some(0) {
+      for song in &self.setlist.songs[self.next_index..] {
+        total = total.merge_unplayed(&song.score.sections);
+      }
+    }
+    self.total = Some(total);
+    self.log = self.log.merge(log);
+  }
+
+  fn total_score(&self) -> GameScore {
+    self
+      .total
+      .clone()
+      .unwrap_or_else(|| GameScore::new(0, 0.0, 0.0))
+  }
+
+  fn title_info(&self) -> MusicInfo {
+    let first_played = self.setlist.songs.first().map_or_else(
+      || MusicInfo::new("", ""),
+      |song| song.score.metadata.get_music_info(),
+    );
+    self.music_info(first_played)
+  }
+
+  fn export_dir(&self) -> PathBuf {
+    self
+      .setlist
+      .export_dir
+      .clone()
+      .unwrap_or_else(|| DEFAULT_EXPORT_DIR.into())
+  }
+
+  fn music_info(&self, last_played: MusicInfo) -> MusicInfo {
+    match &self.setlist.title {
+      Some(title) => MusicInfo::new(
+        title,
+        &format!("全 {} 曲", self.setlist.songs.len()),
+      ),
+      None => last_played,
+    }
+  }
+}
+
+// 読み込めないスキンのときは、組み込みのスキンを使う。色は表示モードに
+// 合わせて置き換える
+fn load_skin(settings: &Settings) -> Skin {
+  let name = &settings.skin;
+  let mut skin = Skin::load(Path::new(SKINS_DIR), name)
+    .unwrap_or_else(|err| {
+      println!(
+        "スキン {} を読み込めなかったので、標準のスキンを使います: {:?}",
+        name, err
+      );
+      Skin::default()
+    });
+  skin.palette = skin.palette.with_mode(settings.display_mode);
+  skin
+}
+
 struct Router<'router> {
+  sdl: &'router Sdl,
   renderer: Renderer<'router>,
   video: &'router Video<'router>,
   font: Rc<Font<'router>>,
@@ -77,12 +280,14 @@ struct Router<'router> {
 
 impl<'router> Router<'router> {
   pub fn new(
+    sdl: &'router Sdl,
     renderer: Renderer<'router>,
     video: &'router Video<'router>,
     font: Font<'router>,
     mix_device: MixDevice<'router>,
   ) -> Self {
     Self {
+      sdl,
       renderer,
       video,
       font: Rc::new(font),
@@ -90,38 +295,162 @@ impl<'router> Router<'router> {
     }
   }
 
-  pub fn run(self, score: Scoremap) -> Result<(), ViewError> {
-    let mut view: Option<Box<dyn View>> =
-      Some(Box::new(ResultView::new(
+  fn screen_view(
+    &self,
+    screen: &Screen,
+    progress: &SetlistProgress,
+  ) -> Box<dyn View + '_> {
+    match screen {
+      Screen::Title => Box::new(TitleView::new(
         &self.renderer,
-        GameScore::new(0, 0.0, 0.0),
-        score.metadata.get_music_info(),
+        progress.title_info(),
         Rc::clone(&self.font),
         self.video,
-      )));
+        progress.skin.clone(),
+      )),
+      Screen::Settings => Box::new(SettingsView::new(
+        &self.renderer,
+        Rc::clone(&self.font),
+        self.video,
+        progress.settings.clone(),
+        progress.skin.clone(),
+      )),
+      Screen::Result(score, info, log) => Box::new(ResultView::new(
+        &self.renderer,
+        score.clone(),
+        info.clone(),
+        log.clone(),
+        progress.export_dir(),
+        Rc::clone(&self.font),
+        self.video,
+        progress.skin.clone(),
+      )),
+    }
+  }
+
+  pub fn run(
+    self,
+    setlist: Setlist,
+    settings: Settings,
+  ) -> Result<(), ViewError> {
+    let mut progress = SetlistProgress::new(&setlist, settings);
+    let mut queued_routes = VecDeque::new();
+    let mut history = vec![Screen::Title];
+    // 曲やずれの測定の画面は戻り先にしない
+    let mut on_transient_view = setlist.calibrate;
+    let mut view: Option<Box<dyn View>> = if setlist.calibrate {
+      Some(Box::new(CalibrationView::new(
+        self.sdl,
+        &self.renderer,
+        Rc::clone(&self.font),
+        &self.mix_device,
+        self.video,
+        progress.settings.clone(),
+        progress.skin.clone(),
+      )))
+    } else {
+      Some(self.screen_view(&Screen::Title, &progress))
+    };
     while let Some(boxed_view) = view.as_mut() {
-      let next = boxed_view.run()?;
+      let next = match queued_routes.pop_front() {
+        Some(route) => route,
+        None => boxed_view.run()?,
+      };
       match next {
         ViewRoute::SelectMusic => {}
-        ViewRoute::Start(_) => {}
-        ViewRoute::Retry => {
+        ViewRoute::Play => {
+          progress.practice = false;
+          queued_routes.push_back(ViewRoute::Retry);
+        }
+        ViewRoute::Practice => {
+          progress.practice = true;
+          queued_routes.push_back(ViewRoute::Retry);
+        }
+        ViewRoute::Start(score) => {
+          on_transient_view = true;
           view.replace(Box::new(GameView::new(
+            self.sdl,
             &self.renderer,
-            score.clone(),
+            score,
             Rc::clone(&self.font),
             &self.mix_device,
             self.video,
+            progress.current_config(),
+            progress.settings.clone(),
+            progress.skin.clone(),
           )?));
         }
-        ViewRoute::ResultView(score, info) => {
+        ViewRoute::Retry => {
+          progress.restart();
+          if let Some(score) = progress.next_score() {
+            queued_routes.push_back(ViewRoute::Start(score));
+          }
+        }
+        ViewRoute::ResultView(score, info, log) => {
+          progress.record(score, &log);
+          // コースの途中なら次の曲へ進む
+          if let Some(next_score) = progress.next_score() {
+            queued_routes.push_back(ViewRoute::Start(next_score));
+            continue;
+          }
+          let total_score = progress.total_score();
+          let info = progress.music_info(info);
+          if let Some(dir) = &setlist.export_dir {
+            let export =
+              ResultExport::new(&info, &total_score, &progress.log);
+            match export.write_to(dir, &default_stem()) {
+              Ok(paths) => {
+                for path in paths {
+                  println!(
+                    "結果を書き出しました: {}",
+                    path.display()
+                  );
+                }
+              }
+              Err(err) => {
+                println!("結果を書き出せませんでした: {}", err)
+              }
+            }
+          }
+          // 再挑戦を重ねても、戻り先の結果は最新の 1 つにする
+          history
+            .retain(|screen| !matches!(screen, Screen::Result(..)));
+          let screen =
+            Screen::Result(total_score, info, progress.log.clone());
+          history.push(screen.clone());
+          on_transient_view = false;
           view = None;
-          view.replace(Box::new(ResultView::new(
-            &self.renderer,
-            score,
-            info,
-            Rc::clone(&self.font),
-            self.video,
-          )));
+          view.replace(self.screen_view(&screen, &progress));
+        }
+        ViewRoute::Calibrated(offset) => {
+          // 打鍵がなければ、これまでのずれのままにする
+          if let Some(offset) = offset {
+            progress.settings.user_offset = offset;
+            progress.save_settings();
+          }
+          queued_routes.push_back(ViewRoute::Back);
+        }
+        ViewRoute::Settings => {
+          history.push(Screen::Settings);
+          on_transient_view = false;
+          view = None;
+          view
+            .replace(self.screen_view(&Screen::Settings, &progress));
+        }
+        ViewRoute::SavedSettings(settings) => {
+          progress.set_settings(settings);
+          progress.save_settings();
+          queued_routes.push_back(ViewRoute::Back);
+        }
+        ViewRoute::Back => {
+          if !on_transient_view {
+            history.pop();
+          }
+          on_transient_view = false;
+          view = None;
+          if let Some(screen) = history.last() {
+            view.replace(self.screen_view(screen, &progress));
+          }
         }
         ViewRoute::Quit => {
           view = None;
@@ -133,7 +462,18 @@ impl<'router> Router<'router> {
   }
 }
 
-pub fn run_router(score: Scoremap) -> Result<(), ViewError> {
+pub fn run_router(setlist: Setlist) -> Result<(), ViewError> {
+  let is_first_run = !Path::new(SETTINGS_PATH).exists();
+  let mut settings = match Settings::load(Path::new(SETTINGS_PATH)) {
+    Ok(settings) => settings,
+    Err(err) => {
+      println!(
+        "設定を読み込めなかったので、既定の設定を使います: {:?}",
+        err
+      );
+      Settings::default()
+    }
+  };
   let sdl = Sdl::new();
   let ttf = Ttf::new();
   let mix = Mix::new(FormatFlag::MP3).expect("mp3 loader not found");
@@ -142,20 +482,39 @@ pub fn run_router(score: Scoremap) -> Result<(), ViewError> {
   let dev =
     builder.build(&mix).expect("Fail to open an audio channel");
 
-  let font =
-    Font::new(&ttf, "./asset/mplus-1m-medium.ttf", 128, None)
-      .expect("Font file is not found");
+  let skin = load_skin(&settings);
+  let font = skin
+    .font
+    .to_str()
+    .and_then(|path| Font::new(&ttf, path, 128, None).ok())
+    .unwrap_or_else(|| {
+      Font::new(&ttf, "./asset/mplus-1m-medium.ttf", 128, None)
+        .expect("Font file is not found")
+    });
 
   let video = Video::new(&sdl);
+  // 設定ファイルがまだなければ、画面のキーボードを OS のキー配列に合わせる
+  if is_first_run {
+    if let Some(layout) = game_view::detect_keyboard_layout() {
+      settings.keyboard_layout = layout;
+    }
+  }
   let window = WindowBuilder::builder()
     .title("Musical Typer")
-    .width(800)
-    .height(600)
+    .width(settings.window_size.0)
+    .height(settings.window_size.1)
+    .format(if settings.fullscreen {
+      WindowFormat::FullScreenWithCurrentDesktop
+    } else {
+      WindowFormat::Normal
+    })
+    .resizable(true)
     .context_kind(WindowContextKind::OpenGl)
     .build()
     .new_window(&video);
   let renderer = Renderer::new(&window);
 
-  Router::new(renderer, &video, font, dev).run(score)?;
+  Router::new(&sdl, renderer, &video, font, dev)
+    .run(setlist, settings)?;
   Ok(())
 }
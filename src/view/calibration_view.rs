@@ -0,0 +1,168 @@
+use rich_sdl2_rust::mixer::device::MixDevice;
+use rich_sdl2_rust::ttf::font::{
+  pen::{
+    FontRenderExt, FontRenderOptions, TextAlign, TextAlignX,
+    TextAlignY,
+  },
+  Font, RenderMode, StyleExt,
+};
+use rich_sdl2_rust::{
+  delay,
+  event::keyboard::scan_code::ScanCode,
+  geo::Point,
+  renderer::{pen::Pen, Renderer},
+  EventBox, Sdl, Ticks, Video,
+};
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
+  time::Instant,
+};
+
+use super::{
+  layout::Layout,
+  opaque,
+  player::{Player, SEKind},
+  View, ViewError, ViewRoute,
+};
+use crate::model::{
+  exp::time::Seconds, game::calibration::Calibration,
+  settings::Settings, skin::Skin,
+};
+
+const CLICK_INTERVAL: f64 = 0.6;
+const CLICKS: usize = 16;
+const EVENTS_PER_FRAME: usize = 64;
+
+pub struct CalibrationView<'view> {
+  sdl: &'view Sdl,
+  renderer: &'view Renderer<'view>,
+  font: Rc<Font<'view>>,
+  device: &'view MixDevice<'view>,
+  video: &'view Video<'view>,
+  settings: Settings,
+  skin: Skin,
+}
+
+impl<'view> CalibrationView<'view> {
+  pub fn new(
+    sdl: &'view Sdl,
+    renderer: &'view Renderer<'view>,
+    font: Rc<Font<'view>>,
+    device: &'view MixDevice<'view>,
+    video: &'view Video<'view>,
+    settings: Settings,
+    skin: Skin,
+  ) -> Self {
+    Self {
+      sdl,
+      renderer,
+      font,
+      device,
+      video,
+      settings,
+      skin,
+    }
+  }
+}
+
+impl<'view> View for CalibrationView<'view> {
+  fn run(&mut self) -> Result<ViewRoute, ViewError> {
+    let mut layout = Layout::of(self.renderer);
+    let mut player = Player::new(self.device, &self.skin)?;
+    player.set_volume(self.settings.volume);
+    let mut calibration =
+      Calibration::new(CLICK_INTERVAL.into(), CLICKS);
+    let mut next_click = 0;
+
+    let should_quit = Cell::new(false);
+    let should_go_back = Cell::new(false);
+    let tap_buf = RefCell::new(vec![]);
+
+    let mut event = EventBox::new(self.video);
+    event.handle_quit(Box::new(|_| should_quit.set(true)));
+    event.handle_keyboard(Box::new(|e| {
+      if e.symbol.scan_code == ScanCode::Escape {
+        should_go_back.set(true);
+        return;
+      }
+      if e.is_pressed && !e.is_repeated {
+        tap_buf.borrow_mut().push(e.timestamp);
+      }
+    }));
+
+    let start_ticks = Ticks::now(self.sdl).0;
+    let ticks_to_time = |ticks: u32| -> Seconds {
+      (ticks.saturating_sub(start_ticks) as f64 / 1e3).into()
+    };
+
+    loop {
+      if should_quit.get() {
+        return Ok(ViewRoute::Quit);
+      }
+      if should_go_back.get() {
+        return Ok(ViewRoute::Back);
+      }
+      let render_start_time = Instant::now();
+      for _ in 0..EVENTS_PER_FRAME {
+        event.poll();
+      }
+      let now = ticks_to_time(Ticks::now(self.sdl).0);
+      while next_click < calibration.clicks()
+        && calibration.click_time(next_click) <= now
+      {
+        player.play_se(SEKind::Click)?;
+        next_click += 1;
+      }
+      for timestamp in tap_buf.borrow_mut().drain(..) {
+        calibration.tap(ticks_to_time(timestamp));
+      }
+      if calibration.end_time() < now {
+        return Ok(ViewRoute::Calibrated(calibration.offset()));
+      }
+
+      if let Some(resized) = layout.resized(self.renderer) {
+        layout = resized;
+      }
+      {
+        let pen = Pen::new(self.renderer);
+        pen.set_color(self.skin.palette.background.into());
+        pen.clear();
+
+        let text_options = |pivot: Point| {
+          FontRenderOptions::new()
+            .mode(RenderMode::Blended {
+              foreground: opaque(self.skin.palette.text),
+            })
+            .align(TextAlign {
+              x: TextAlignX::Center,
+              y: TextAlignY::Center,
+            })
+            .pivot(pivot)
+        };
+        let center = layout.client().center();
+        self.font.set_font_size(layout.font(30)).unwrap();
+        pen.text(
+          &self.font,
+          "クリック音に合わせてキーを打ってください",
+          text_options(layout.offset(center, 0, -60)),
+        );
+        pen.text(
+          &self.font,
+          &format!("{} / {}", next_click, calibration.clicks()),
+          text_options(center),
+        );
+        if let Some(offset) = calibration.offset() {
+          pen.text(
+            &self.font,
+            &format!("ずれ {:+.0} ms", offset.as_f64() * 1e3),
+            text_options(layout.offset(center, 0, 60)),
+          );
+        }
+      }
+
+      let draw_time = render_start_time.elapsed().as_secs_f64();
+      delay((1e3 / 60.0 - draw_time * 1e3).max(0.0) as u32);
+    }
+  }
+}
@@ -1,7 +1,13 @@
 mod button;
+mod choice;
 mod header;
+mod slider;
 mod stats;
+mod toggle;
 
 pub use button::*;
+pub use choice::*;
 pub use header::*;
+pub use slider::*;
 pub use stats::*;
+pub use toggle::*;
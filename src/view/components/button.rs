@@ -2,7 +2,7 @@ use crate::view::Component;
 use rich_sdl2_rust::{
   color::Rgb,
   event::mouse::{MouseButton, MouseEvent, MouseMotionEvent},
-  geo::Rect,
+  geo::{Point, Rect},
   renderer::pen::Pen,
 };
 
@@ -16,26 +16,43 @@ impl PartialEq for ButtonProps {
   fn eq(&self, other: &Self) -> bool {
     self.border_color == other.border_color
       && self.color_on_hover == other.color_on_hover
-      && (self.mouse.is_some()
-        && other.mouse.is_some()
-        && match (
-          self.mouse.as_ref().unwrap(),
-          other.mouse.as_ref().unwrap(),
-        ) {
-          (
-            MouseEvent::Motion(MouseMotionEvent { pos, .. }),
-            MouseEvent::Motion(MouseMotionEvent {
-              pos: other_pos,
-              ..
-            }),
-          ) => pos == other_pos,
-          (MouseEvent::Button(_), MouseEvent::Button(_)) => true,
-          _ => false,
-        }
-        || self.mouse.is_none() && other.mouse.is_none())
+      && is_same_mouse(&self.mouse, &other.mouse)
   }
 }
 
+// 同じ位置への移動や続けてのクリックは、同じマウスの状態とみなす
+pub fn is_same_mouse(
+  mouse: &Option<MouseEvent>,
+  other: &Option<MouseEvent>,
+) -> bool {
+  match (mouse, other) {
+    (
+      Some(MouseEvent::Motion(MouseMotionEvent { pos, .. })),
+      Some(MouseEvent::Motion(MouseMotionEvent {
+        pos: other_pos,
+        ..
+      })),
+    ) => pos == other_pos,
+    (Some(MouseEvent::Button(_)), Some(MouseEvent::Button(_))) => {
+      true
+    }
+    (None, None) => true,
+    _ => false,
+  }
+}
+
+// 左クリックで押されたときの位置
+pub fn clicked_pos(mouse: &Option<MouseEvent>) -> Option<Point> {
+  if let Some(MouseEvent::Button(button)) = mouse {
+    if let Some(MouseButton::Left) = button.button {
+      if button.is_pressed {
+        return Some(button.pos);
+      }
+    }
+  }
+  None
+}
+
 pub struct Button<H> {
   props: ButtonProps,
   bounds: Rect,
@@ -62,11 +79,9 @@ impl<H: FnMut()> Component for Button<H> {
   fn update(&mut self, props: Self::Props) {
     self.props = props;
 
-    if let Some(MouseEvent::Button(ref button)) = self.props.mouse {
-      if let Some(MouseButton::Left) = button.button {
-        if button.is_pressed && button.pos.is_in(self.bounds) {
-          (self.on_click)();
-        }
+    if let Some(pos) = clicked_pos(&self.props.mouse) {
+      if pos.is_in(self.bounds) {
+        (self.on_click)();
       }
     }
   }
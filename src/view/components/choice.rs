@@ -0,0 +1,123 @@
+use super::{clicked_pos, is_same_mouse};
+use crate::{
+  model::skin::Palette,
+  view::{layout::Layout, opaque, Component},
+};
+use rich_sdl2_rust::{
+  event::mouse::MouseEvent,
+  geo::{Point, Rect, Size},
+  renderer::pen::Pen,
+  ttf::font::{
+    pen::{
+      FontRenderExt, FontRenderOptions, TextAlign, TextAlignX,
+      TextAlignY,
+    },
+    Font, RenderMode, StyleExt,
+  },
+};
+use std::rc::Rc;
+
+pub struct ChoiceProps {
+  pub options: Vec<String>,
+  pub selected: usize,
+  pub mouse: Option<MouseEvent>,
+}
+
+impl PartialEq for ChoiceProps {
+  fn eq(&self, other: &Self) -> bool {
+    self.options == other.options
+      && self.selected == other.selected
+      && is_same_mouse(&self.mouse, &other.mouse)
+  }
+}
+
+// 横に並べた選択肢から 1 つを選ぶ
+pub struct Choice<'font, H> {
+  props: ChoiceProps,
+  font: Rc<Font<'font>>,
+  bounds: Rect,
+  layout: Layout,
+  palette: Palette,
+  on_select: H,
+}
+
+impl<'font, H: FnMut(usize)> Choice<'font, H> {
+  pub fn new(
+    props: ChoiceProps,
+    font: Rc<Font<'font>>,
+    bounds: Rect,
+    layout: Layout,
+    palette: Palette,
+    on_select: H,
+  ) -> Self {
+    Self {
+      props,
+      font,
+      bounds,
+      layout,
+      palette,
+      on_select,
+    }
+  }
+
+  fn cell(&self, index: usize) -> Rect {
+    let width =
+      self.bounds.size.width / self.props.options.len().max(1) as u32;
+    Rect {
+      up_left: Point {
+        x: self.bounds.left() + (width * index as u32) as i32,
+        y: self.bounds.top(),
+      },
+      size: Size {
+        width,
+        height: self.bounds.size.height,
+      },
+    }
+  }
+}
+
+impl<'font, H: FnMut(usize)> Component for Choice<'font, H> {
+  type Props = ChoiceProps;
+
+  fn is_needed_redraw(&self, new_props: &Self::Props) -> bool {
+    &self.props != new_props
+  }
+
+  fn update(&mut self, props: Self::Props) {
+    self.props = props;
+
+    if let Some(pos) = clicked_pos(&self.props.mouse) {
+      let clicked = (0..self.props.options.len())
+        .find(|&index| pos.is_in(self.cell(index)));
+      if let Some(index) = clicked {
+        (self.on_select)(index);
+      }
+    }
+  }
+
+  fn render(&self, pen: &Pen<'_>) {
+    self.font.set_font_size(self.layout.font(20)).unwrap();
+    for (index, option) in self.props.options.iter().enumerate() {
+      let cell = self.cell(index);
+      if index == self.props.selected {
+        pen.set_color(self.palette.hover.into());
+        pen.fill_rect(cell);
+      }
+      pen.set_color(self.palette.border.into());
+      pen.stroke_rect(cell);
+      pen.text(
+        &self.font,
+        option,
+        FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(self.palette.text),
+          })
+          .align(TextAlign {
+            x: TextAlignX::Center,
+            y: TextAlignY::Center,
+          })
+          .pivot(cell.center()),
+      );
+    }
+  }
+}
@@ -8,7 +8,10 @@ use rich_sdl2_rust::{
 use rich_sdl2_rust::{color::Rgba, geo::Rect, renderer::pen::Pen};
 use std::{rc::Rc, time::Instant};
 
-use crate::{model::exp::scoremap::MusicInfo, view::Component};
+use crate::{
+  model::{exp::scoremap::MusicInfo, skin::Palette},
+  view::{layout::Layout, opaque, Component},
+};
 
 #[derive(PartialEq)]
 pub struct HeaderProps {
@@ -28,6 +31,8 @@ pub struct Header<'font> {
   font: Rc<Font<'font>>,
   animating_texts: Vec<AnimatedText>,
   client: Rect,
+  layout: Layout,
+  palette: Palette,
 }
 
 pub struct AnimatedText {
@@ -43,12 +48,16 @@ impl<'font> Header<'font> {
     props: HeaderProps,
     font: Rc<Font<'font>>,
     client: Rect,
+    layout: Layout,
+    palette: Palette,
   ) -> Self {
     Self {
       props,
       font,
       animating_texts: vec![],
       client,
+      layout,
+      palette,
     }
   }
 }
@@ -74,21 +83,19 @@ impl<'font> Component for Header<'font> {
       let text = match res {
         SentenceResult::Completed => AnimatedText {
           created_at: Instant::now(),
-          text: "AC".into(),
-          color: Rgb {
-            r: 0x72,
-            g: 0xb5,
-            b: 0x66,
-          },
+          text: if self.palette.markers { "○ AC" } else { "AC" }
+            .into(),
+          color: self.palette.good.into(),
         },
         SentenceResult::Missed => AnimatedText {
           created_at: Instant::now(),
-          text: "TLE".into(),
-          color: Rgb {
-            r: 0xe7,
-            g: 0xb0,
-            b: 0x5f,
-          },
+          text: if self.palette.markers {
+            "× TLE"
+          } else {
+            "TLE"
+          }
+          .into(),
+          color: self.palette.warning.into(),
         },
       };
       self.animating_texts.push(text);
@@ -101,6 +108,8 @@ impl<'font> Component for Header<'font> {
       props,
       animating_texts,
       client,
+      layout,
+      palette,
     } = &self;
     let &HeaderProps {
       music_info,
@@ -111,60 +120,45 @@ impl<'font> Component for Header<'font> {
     let title = &music_info.title;
     let author = &music_info.song_author;
 
-    font.set_font_size(30).unwrap();
+    font.set_font_size(layout.font(30)).unwrap();
     pen.text(
       font,
       title,
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
-          foreground: Rgba {
-            r: 0,
-            g: 0,
-            b: 0,
-            a: 255,
-          },
+          foreground: opaque(palette.text),
         })
         .align(TextAlign {
           x: TextAlignX::Right,
           ..Default::default()
         })
-        .pivot(client.top_right().offset(-5, 5)),
+        .pivot(layout.offset(client.top_right(), -5, 5)),
     );
 
-    font.set_font_size(15).unwrap();
+    font.set_font_size(layout.font(15)).unwrap();
     pen.text(
       font,
       author,
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
-          foreground: Rgba {
-            r: 156,
-            g: 156,
-            b: 162,
-            a: 255,
-          },
+          foreground: opaque(palette.label),
         })
         .align(TextAlign {
           x: TextAlignX::Right,
           ..Default::default()
         })
-        .pivot(client.bottom_right().offset(-5, -35)),
+        .pivot(layout.offset(client.bottom_right(), -5, -35)),
     );
 
-    font.set_font_size(35).unwrap();
+    font.set_font_size(layout.font(35)).unwrap();
     pen.text(
       font,
       &format!("{:08}", score_point),
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
-          foreground: Rgba {
-            r: 64,
-            g: 79,
-            b: 181,
-            a: 255,
-          },
+          foreground: opaque(palette.accent),
         })
-        .pivot(client.bottom_left().offset(5, -60)),
+        .pivot(layout.offset(client.bottom_left(), 5, -60)),
     );
 
     for AnimatedText {
@@ -179,7 +173,7 @@ impl<'font> Component for Header<'font> {
       let y_offset = 1.0 - (ANIMATION_SPEED * norm_time).exp2();
       let opacity = (255.0 * (1.0 - norm_time)) as u8;
 
-      font.set_font_size(28).unwrap();
+      font.set_font_size(layout.font(28)).unwrap();
       pen.text(
         font,
         text,
@@ -188,15 +182,15 @@ impl<'font> Component for Header<'font> {
             foreground: Rgba {
               r: color.r,
               g: color.g,
-              b: color.g,
+              b: color.b,
               a: opacity,
             },
           })
-          .pivot(
-            client
-              .bottom_left()
-              .offset(180, (-60.0 + y_offset) as i32),
-          ),
+          .pivot(layout.offset(
+            client.bottom_left(),
+            180,
+            (-60.0 + y_offset) as i32,
+          )),
       );
     }
   }
@@ -0,0 +1,121 @@
+use super::{clicked_pos, is_same_mouse};
+use crate::{model::skin::Palette, view::Component};
+use rich_sdl2_rust::{
+  event::mouse::MouseEvent,
+  geo::{Point, Rect, Size},
+  renderer::pen::Pen,
+};
+
+const TRACK_HEIGHT: u32 = 4;
+const KNOB_WIDTH: u32 = 12;
+
+pub struct SliderProps {
+  pub value: u32,
+  pub max: u32,
+  pub mouse: Option<MouseEvent>,
+}
+
+impl PartialEq for SliderProps {
+  fn eq(&self, other: &Self) -> bool {
+    self.value == other.value
+      && self.max == other.max
+      && is_same_mouse(&self.mouse, &other.mouse)
+  }
+}
+
+// 0 から max までの値を、クリックした位置で選ぶ
+pub struct Slider<H> {
+  props: SliderProps,
+  bounds: Rect,
+  palette: Palette,
+  on_change: H,
+}
+
+impl<H: FnMut(u32)> Slider<H> {
+  pub fn new(
+    props: SliderProps,
+    bounds: Rect,
+    palette: Palette,
+    on_change: H,
+  ) -> Self {
+    Self {
+      props,
+      bounds,
+      palette,
+      on_change,
+    }
+  }
+
+  fn value_at(&self, x: i32) -> u32 {
+    let width = self.bounds.size.width.max(1) as f64;
+    let ratio =
+      ((x - self.bounds.left()) as f64 / width).clamp(0.0, 1.0);
+    (ratio * self.props.max as f64).round() as u32
+  }
+}
+
+impl<H: FnMut(u32)> Component for Slider<H> {
+  type Props = SliderProps;
+
+  fn is_needed_redraw(&self, new_props: &Self::Props) -> bool {
+    &self.props != new_props
+  }
+
+  fn update(&mut self, props: Self::Props) {
+    self.props = props;
+
+    if let Some(pos) = clicked_pos(&self.props.mouse) {
+      if pos.is_in(self.bounds) {
+        let value = self.value_at(pos.x);
+        (self.on_change)(value);
+      }
+    }
+  }
+
+  fn render(&self, pen: &Pen<'_>) {
+    let bounds = self.bounds;
+    let ratio = if self.props.max == 0 {
+      0.0
+    } else {
+      self.props.value as f64 / self.props.max as f64
+    };
+    let track_top = bounds.center().y - TRACK_HEIGHT as i32 / 2;
+
+    pen.set_color(self.palette.hover.into());
+    pen.fill_rect(Rect {
+      up_left: Point {
+        x: bounds.left(),
+        y: track_top,
+      },
+      size: Size {
+        width: bounds.size.width,
+        height: TRACK_HEIGHT,
+      },
+    });
+    let filled_width = (bounds.size.width as f64 * ratio) as u32;
+    pen.set_color(self.palette.highlight.into());
+    pen.fill_rect(Rect {
+      up_left: Point {
+        x: bounds.left(),
+        y: track_top,
+      },
+      size: Size {
+        width: filled_width,
+        height: TRACK_HEIGHT,
+      },
+    });
+
+    pen.set_color(self.palette.border.into());
+    pen.fill_rect(Rect {
+      up_left: Point {
+        x: bounds.left() + filled_width as i32
+          - KNOB_WIDTH as i32 / 2,
+        y: bounds.top(),
+      },
+      size: Size {
+        width: KNOB_WIDTH,
+        height: bounds.size.height,
+      },
+    });
+  }
+}
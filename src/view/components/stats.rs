@@ -12,7 +12,10 @@ use rich_sdl2_rust::{
 };
 use std::rc::Rc;
 
-use crate::{model::exp::game_activity::GameScore, view::Component};
+use crate::{
+  model::{exp::game_activity::GameScore, skin::Palette},
+  view::{layout::Layout, opaque, Component},
+};
 
 mod rank;
 
@@ -26,6 +29,8 @@ pub struct Stats<'font> {
   props: StatsProps,
   font: Rc<Font<'font>>,
   client: Rect,
+  layout: Layout,
+  palette: Palette,
 }
 
 impl<'font> Stats<'font> {
@@ -33,11 +38,15 @@ impl<'font> Stats<'font> {
     props: StatsProps,
     font: Rc<Font<'font>>,
     client: Rect,
+    layout: Layout,
+    palette: Palette,
   ) -> Self {
     Self {
       props,
       font,
       client,
+      layout,
+      palette,
     }
   }
 }
@@ -58,6 +67,8 @@ impl<'font> Component for Stats<'font> {
       props,
       font,
       client,
+      layout,
+      palette,
     } = &self;
     let StatsProps {
       type_per_second,
@@ -67,36 +78,29 @@ impl<'font> Component for Stats<'font> {
     let accuracy = score.accuracy;
     let achievement_rate = score.achievement_rate;
 
-    let speed_indicator_color = if 4.0 < *type_per_second {
-      Rgb {
-        r: 250,
-        g: 119,
-        b: 109,
-      }
+    let danger: Rgb = palette.danger.into();
+    let speed_indicator_color: Rgb = if 4.0 < *type_per_second {
+      palette.danger.into()
     } else {
-      Rgb {
-        r: 178,
-        g: 255,
-        b: 89,
-      }
+      palette.calm.into()
     };
 
     let rank = rank::rank(accuracy * 200.0);
 
     let speed_indicator_center = Point {
       x: client.size.width as i32 / 2,
-      y: client.up_left.y + 15,
+      y: client.up_left.y + layout.px(15),
     };
     pen.set_color(speed_indicator_color);
     pen.fill_rect(Rect::from_center(
       speed_indicator_center,
       Size {
-        width: client.size.width - 20,
-        height: 20,
+        width: client.size.width - layout.dim(20),
+        height: layout.dim(20),
       },
     ));
 
-    font.set_font_size(10).unwrap();
+    font.set_font_size(layout.font(10)).unwrap();
     pen.text(
       font,
       &format!("{:04.2} Type/s", type_per_second),
@@ -108,119 +112,110 @@ impl<'font> Component for Stats<'font> {
         .pivot(speed_indicator_center),
     );
 
-    font.set_font_size(15).unwrap();
+    font.set_font_size(layout.font(15)).unwrap();
     pen.text(
       font,
       "正解率",
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
-          foreground: Rgba {
-            r: 160,
-            g: 160,
-            b: 165,
-            a: 255,
-          },
+          foreground: opaque(palette.label),
         })
-        .pivot(client.up_left.offset(10, 30)),
+        .pivot(layout.offset(client.up_left, 10, 30)),
     );
-    font.set_font_size(80).unwrap();
+    font.set_font_size(layout.font(80)).unwrap();
     pen.text(
       font,
       &format!("{:05.1}%", accuracy * 100.0),
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
           foreground: Rgba {
-            r: (250.0 * accuracy) as u8,
-            g: (120.0 * accuracy) as u8,
-            b: (110.0 * accuracy) as u8,
+            r: (danger.r as f64 * accuracy) as u8,
+            g: (danger.g as f64 * accuracy) as u8,
+            b: (danger.b as f64 * accuracy) as u8,
             a: 255,
           },
         })
-        .pivot(client.up_left.offset(10, 30)),
+        .pivot(layout.offset(client.up_left, 10, 30)),
     );
 
-    pen.set_color(Rgb {
-      r: 250,
-      g: 120,
-      b: 110,
-    });
+    pen.set_color(danger);
     pen.stroke_rect(Rect {
       up_left: Point {
-        x: client.left() + 10,
-        y: client.bottom() - 10,
+        x: client.left() + layout.px(10),
+        y: client.bottom() - layout.px(10),
       },
       size: Size {
         width: (client.size.width as f64 * 0.5 * accuracy) as u32,
-        height: 2,
+        height: layout.dim(2).max(1),
       },
     });
 
-    font.set_font_size(15).unwrap();
+    font.set_font_size(layout.font(15)).unwrap();
     pen.text(
       font,
       "達成率",
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
-          foreground: Rgba {
-            r: 160,
-            g: 160,
-            b: 165,
-            a: 255,
-          },
+          foreground: opaque(palette.label),
         })
         .pivot(Point {
-          x: client.center().x + client.left() + 10,
-          y: client.up_left.y + 30,
+          x: client.center().x + client.left() + layout.px(10),
+          y: client.up_left.y + layout.px(30),
         }),
     );
-    font.set_font_size(80).unwrap();
+    font.set_font_size(layout.font(80)).unwrap();
     pen.text(
       font,
       &format!("{:05.1}%", achievement_rate * 100.0),
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
-          foreground: Rgba {
-            r: 64,
-            g: 79,
-            b: 181,
-            a: 255,
-          },
+          foreground: opaque(palette.accent),
         })
         .pivot(Point {
-          x: client.center().x + client.left() + 10,
-          y: client.up_left.y + 30,
+          x: client.center().x + client.left() + layout.px(10),
+          y: client.up_left.y + layout.px(30),
         }),
     );
 
-    font.set_font_size(12).unwrap();
+    font.set_font_size(layout.font(12)).unwrap();
     pen.text(
       font,
       "ランク",
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
-          foreground: Rgba {
-            r: 160,
-            g: 160,
-            b: 165,
-            a: 255,
-          },
+          foreground: opaque(palette.label),
         })
-        .pivot(client.up_left.offset(10, -60)),
+        .pivot(layout.offset(client.up_left, 10, -60)),
     );
-    font.set_font_size(16).unwrap();
+    font.set_font_size(layout.font(16)).unwrap();
     pen.text(
       font,
       rank.0,
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
-          foreground: Rgba {
-            r: 64,
-            g: 79,
-            b: 181,
-            a: 255,
-          },
+          foreground: opaque(palette.accent),
         })
-        .pivot(client.up_left.offset(10, -30)),
+        .pivot(layout.offset(client.up_left, 10, -30)),
     );
+
+    if let Some(life) = score.remaining_life {
+      font.set_font_size(layout.font(16)).unwrap();
+      pen.text(
+        font,
+        &format!("ライフ {}", life),
+        FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(palette.danger),
+          })
+          .align(TextAlign {
+            x: TextAlignX::Right,
+            ..Default::default()
+          })
+          .pivot(Point {
+            x: client.right() - layout.px(10),
+            y: client.top() - layout.px(30),
+          }),
+      );
+    }
   }
 }
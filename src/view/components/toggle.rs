@@ -0,0 +1,99 @@
+use super::{clicked_pos, is_same_mouse};
+use crate::{model::skin::Palette, view::Component};
+use rich_sdl2_rust::{
+  event::mouse::MouseEvent,
+  geo::{Point, Rect, Size},
+  renderer::pen::Pen,
+};
+
+const PADDING: u32 = 4;
+
+pub struct ToggleProps {
+  pub on: bool,
+  pub mouse: Option<MouseEvent>,
+}
+
+impl PartialEq for ToggleProps {
+  fn eq(&self, other: &Self) -> bool {
+    self.on == other.on && is_same_mouse(&self.mouse, &other.mouse)
+  }
+}
+
+// クリックするたびに入と切を切り替える
+pub struct Toggle<H> {
+  props: ToggleProps,
+  bounds: Rect,
+  palette: Palette,
+  on_toggle: H,
+  // 切り替えた後も同じクリックが届くので、一度だけ反応するよう覚えておく
+  last_click: Option<u32>,
+}
+
+impl<H: FnMut(bool)> Toggle<H> {
+  pub fn new(
+    props: ToggleProps,
+    bounds: Rect,
+    palette: Palette,
+    on_toggle: H,
+  ) -> Self {
+    Self {
+      props,
+      bounds,
+      palette,
+      on_toggle,
+      last_click: None,
+    }
+  }
+}
+
+impl<H: FnMut(bool)> Component for Toggle<H> {
+  type Props = ToggleProps;
+
+  fn is_needed_redraw(&self, new_props: &Self::Props) -> bool {
+    &self.props != new_props
+  }
+
+  fn update(&mut self, props: Self::Props) {
+    self.props = props;
+
+    if let Some(pos) = clicked_pos(&self.props.mouse) {
+      let timestamp = match &self.props.mouse {
+        Some(MouseEvent::Button(button)) => Some(button.timestamp),
+        _ => None,
+      };
+      if pos.is_in(self.bounds) && self.last_click != timestamp {
+        self.last_click = timestamp;
+        let on = !self.props.on;
+        (self.on_toggle)(on);
+      }
+    }
+  }
+
+  fn render(&self, pen: &Pen<'_>) {
+    let bounds = self.bounds;
+    if self.props.on {
+      pen.set_color(self.palette.highlight.into());
+      pen.fill_rect(bounds);
+    }
+    pen.set_color(self.palette.border.into());
+    pen.stroke_rect(bounds);
+
+    // つまみは入なら右、切なら左に置く
+    let knob_size = bounds.size.height.saturating_sub(PADDING * 2);
+    let knob_x = if self.props.on {
+      bounds.right() - (PADDING + knob_size) as i32
+    } else {
+      bounds.left() + PADDING as i32
+    };
+    pen.fill_rect(Rect {
+      up_left: Point {
+        x: knob_x,
+        y: bounds.top() + PADDING as i32,
+      },
+      size: Size {
+        width: knob_size,
+        height: knob_size,
+      },
+    });
+  }
+}
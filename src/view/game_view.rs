@@ -1,11 +1,24 @@
+use kana_layout::{
+  key_to_kana, scan_code_to_key, CHARACTER_SCAN_CODES,
+};
 use rich_sdl2_rust::mixer::device::MixDevice;
-use rich_sdl2_rust::ttf::font::Font;
+use rich_sdl2_rust::ttf::font::{
+  pen::{
+    FontRenderExt, FontRenderOptions, TextAlign, TextAlignX,
+    TextAlignY,
+  },
+  Font, RenderMode, StyleExt,
+};
 use rich_sdl2_rust::{
   delay,
-  event::keyboard::key_code::KeyCode,
-  geo::Rect,
+  event::{
+    keyboard::{
+      key_code::KeyCode, key_mod::KeyMod, scan_code::ScanCode,
+    },
+    text::TextInput,
+  },
   renderer::{pen::Pen, Renderer},
-  EventBox, Video,
+  EventBox, Sdl, Ticks, Video,
 };
 use std::{
   cell::{Cell, RefCell},
@@ -21,39 +34,82 @@ use super::{
 };
 use crate::{
   model::{
-    exp::{scoremap::Scoremap, sentence::Sentence, time::Seconds},
+    exp::{
+      scoremap::Scoremap,
+      sentence::{InputMethod, Sentence},
+      time::Seconds,
+    },
     game::{
       MusicalTypeResult, MusicalTyper, MusicalTyperConfig,
       MusicalTyperEvent,
     },
+    keyboard_layout::KeyboardLayout,
+    settings::Settings,
+    skin::Skin,
+  },
+  view::{
+    components::SentenceResult, layout::Layout, opaque, Component,
   },
-  view::{components::SentenceResult, Component},
 };
 
+mod kana_layout;
 mod whole;
 
+// OS のキー配列で文字のキーに割り当てられた文字から、画面のキーボードの配列を選ぶ
+pub fn detect_keyboard_layout() -> Option<KeyboardLayout> {
+  let legends: Vec<_> = CHARACTER_SCAN_CODES
+    .iter()
+    .filter_map(|&scan_code| {
+      let name = KeyCode::from(scan_code).name()?;
+      let mut chars = name.chars();
+      match (chars.next(), chars.next()) {
+        (Some(c), None) => {
+          Some((scan_code_to_key(scan_code), c.to_ascii_lowercase()))
+        }
+        _ => None,
+      }
+    })
+    .collect();
+  KeyboardLayout::detect(&legends)
+}
+
+const EVENTS_PER_FRAME: usize = 64;
+const COUNTDOWN_SECONDS: f64 = 3.0;
+// 先の歌詞として並べる文の数
+const UPCOMING_SENTENCES: usize = 2;
+
 pub struct GameView<'view> {
+  sdl: &'view Sdl,
   renderer: &'view Renderer<'view>,
   model: MusicalTyper,
   font: Rc<Font<'view>>,
   device: &'view MixDevice<'view>,
   video: &'view Video<'view>,
+  settings: Settings,
+  skin: Skin,
 }
 
 impl<'view> GameView<'view> {
   pub fn new(
+    sdl: &'view Sdl,
     renderer: &'view Renderer<'view>,
     score: Scoremap,
     font: Rc<Font<'view>>,
     device: &'view MixDevice<'view>,
     video: &'view Video<'view>,
+    config: MusicalTyperConfig,
+    settings: Settings,
+    skin: Skin,
   ) -> Result<Self, ViewError> {
     Ok(GameView {
+      sdl,
       renderer,
-      model: MusicalTyper::new(score, MusicalTyperConfig::default())?,
+      model: MusicalTyper::new(score, config)?,
       font,
       device,
       video,
+      settings,
+      skin,
     })
   }
 }
@@ -63,19 +119,30 @@ impl<'canvas> View for GameView<'canvas> {
     struct TypeTimePoint(Seconds);
 
     let mut mt_events = vec![];
-    let mut player = Player::new(self.device);
-    let mut sentence = Sentence::empty();
+    let mut player = Player::new(self.device, &self.skin)?;
+    player.set_volume(self.settings.volume);
+    // 曲を流すときは、始めから曲の再生位置で時刻を進める
+    if self.model.has_song() {
+      self.model.set_clock(Box::new(player.music_clock()));
+    }
+    // 始まるまでは最初の文を見せておく
+    let mut sentence = self.model.activity().first_sentence();
     let mut time_points = VecDeque::new();
     let mut ended = None;
 
     let pressed_key_buf = Rc::new(RefCell::new(BTreeSet::new()));
-    let typed_key_buf = Rc::new(RefCell::new(vec![]));
+    // 押された順に、文字と SDL のタイムスタンプを積む
+    let typed_key_buf = Rc::new(RefCell::new(VecDeque::new()));
     let should_quit = Cell::new(false);
+    let should_go_back = Cell::new(false);
+    let input_method = self.model.input_method();
+    // 譜面でそのまま打つよう指定された文は、入力方式の設定によらず文字を打つ
+    let sentence_method = Cell::new(input_method);
+    // 最後に押したキーの時刻。キーリピートだったときは None にする
+    let last_press = Cell::new(None::<u32>);
+    let skip_countdown = Cell::new(!self.settings.countdown);
 
-    let client = Rect {
-      up_left: Default::default(),
-      size: self.renderer.output_size().unwrap(),
-    };
+    let mut layout = Layout::of(self.renderer);
     let mut whole_view = Whole::new(
       WholeProps {
         pressed_keys: pressed_key_buf
@@ -84,14 +151,20 @@ impl<'canvas> View for GameView<'canvas> {
           .cloned()
           .collect(),
         sentence: sentence.clone(),
+        upcoming: self.model.upcoming_sentences(UPCOMING_SENTENCES),
         sentence_result: None,
         music_info: self.model.music_info(),
         type_per_second: 0.0,
         score: self.model.activity().score().clone(),
         section_remaining_ratio: self.model.section_remaining_ratio(),
+        input_method,
+        spelling_hints: self.settings.spelling_hints,
+        keyboard_layout: self.settings.keyboard_layout,
+        finger_guide: self.settings.finger_guide,
       },
       Rc::clone(&self.font),
-      client,
+      layout,
+      self.skin.palette,
     );
 
     let mut event = EventBox::new(self.video);
@@ -101,29 +174,66 @@ impl<'canvas> View for GameView<'canvas> {
     }));
 
     event.handle_keyboard(Box::new(|e| {
+      if e.is_pressed {
+        last_press.set((!e.is_repeated).then(|| e.timestamp));
+      }
       if e.is_repeated {
         return;
       }
-      let key_code = e.symbol.key_code;
-      if e.is_pressed {
-        let key = keycode_to_char(key_code);
-        if pressed_key_buf.borrow_mut().insert(key) {
-          typed_key_buf.borrow_mut().push(key);
+      // エスケープキーで曲をやめて前の画面に戻る
+      if e.is_pressed && e.symbol.scan_code == ScanCode::Escape {
+        should_go_back.set(true);
+        return;
+      }
+      // 押しているキーの表示は、打つ文字によらずキーの位置で決める
+      let key = scan_code_to_key(e.symbol.scan_code);
+      if !e.is_pressed {
+        pressed_key_buf.borrow_mut().remove(&key);
+        return;
+      }
+      pressed_key_buf.borrow_mut().insert(key);
+      skip_countdown.set(true);
+      if key == '\0' {
+        return;
+      }
+      if sentence_method.get() == InputMethod::Kana {
+        let shifted = e.symbol.key_mod.intersects(KeyMod::SHIFT);
+        if let Some(kana) = key_to_kana(key, shifted) {
+          typed_key_buf.borrow_mut().push_back((kana, e.timestamp));
         }
-      } else {
-        pressed_key_buf
-          .borrow_mut()
-          .remove(&keycode_to_char(key_code));
       }
     }));
 
-    let game_start_time = Instant::now();
+    // 配列や Shift を反映した文字は、テキスト入力のイベントから受け取る
+    event.handle_input(Box::new(|e| {
+      // 押しっぱなしによるキーリピートの文字は打鍵として数えない。
+      // 直前のキーの押下がリピートでなく、この文字より前のときだけ受け取る
+      let pressed_at = match last_press.take() {
+        Some(pressed_at) if pressed_at <= e.timestamp => pressed_at,
+        _ => return,
+      };
+      let method = sentence_method.get();
+      for c in e.text.chars() {
+        let typed = match method {
+          InputMethod::Roman => c.to_ascii_lowercase(),
+          InputMethod::Text => c,
+          InputMethod::Kana => continue,
+        };
+        typed_key_buf.borrow_mut().push_back((typed, pressed_at));
+      }
+    }));
+    let _text_input = TextInput::new(self.video, layout.client());
+    let countdown_start = Instant::now();
 
     loop {
       if should_quit.get() {
         player.stop_bgm(50)?;
         return Ok(ViewRoute::Quit);
       }
+      if should_go_back.get() {
+        player.stop_bgm(50)?;
+        return Ok(ViewRoute::Back);
+      }
       let render_start_time = Instant::now();
       let mut sentence_result = None;
       {
@@ -135,6 +245,7 @@ impl<'canvas> View for GameView<'canvas> {
             }
             UpdateSentence(new_sentence) => {
               sentence = new_sentence.clone();
+              sentence_method.set(sentence.input_method());
             }
             Typed(result) => match result {
               MusicalTypeResult::Missed => {
@@ -167,10 +278,16 @@ impl<'canvas> View for GameView<'canvas> {
                 ended = Some(self.model.current_time() + 2.0.into());
               }
             }
+            ChangedNote(_) | StartedSection(_) | EndedSection(_)
+            | ChangedCaption(_) | ChangedCombo(_)
+            | AdjustedScore(_) => {}
           }
         }
       };
-      event.poll();
+      // 1 回の poll ではイベントを 1 つしか処理しないので、溜まった分を続けて処理する
+      for _ in 0..EVENTS_PER_FRAME {
+        event.poll();
+      }
       {
         let expire_limit = self.model.current_time() - 5.0.into();
         while let Some(front) = time_points.front() {
@@ -183,15 +300,17 @@ impl<'canvas> View for GameView<'canvas> {
       }
 
       let type_per_second = time_points.len() as f64 / 5.0;
+      self.model.observe_type_speed(type_per_second);
       {
         let pen = Pen::new(self.renderer);
-        whole_view.update(WholeProps {
+        let props = WholeProps {
           pressed_keys: pressed_key_buf
             .borrow()
             .iter()
             .cloned()
             .collect(),
           sentence: sentence.clone(),
+          upcoming: self.model.upcoming_sentences(UPCOMING_SENTENCES),
           sentence_result,
           music_info: self.model.music_info(),
           type_per_second,
@@ -199,22 +318,85 @@ impl<'canvas> View for GameView<'canvas> {
           section_remaining_ratio: self
             .model
             .section_remaining_ratio(),
-        });
+          input_method,
+          spelling_hints: self.settings.spelling_hints,
+          keyboard_layout: self.settings.keyboard_layout,
+          finger_guide: self.settings.finger_guide,
+        };
+        // ウィンドウの大きさが変わったら、画面の部品を並べ直す
+        if let Some(resized) = layout.resized(self.renderer) {
+          layout = resized;
+          whole_view = Whole::new(
+            props,
+            Rc::clone(&self.font),
+            layout,
+            self.skin.palette,
+          );
+        } else {
+          whole_view.update(props);
+        }
         whole_view.render(&pen);
+
+        if !self.model.is_started() {
+          let remaining = COUNTDOWN_SECONDS
+            - countdown_start.elapsed().as_secs_f64();
+          let text_options = |pivot| {
+            FontRenderOptions::new()
+              .mode(RenderMode::Blended {
+                foreground: opaque(self.skin.palette.highlight),
+              })
+              .align(TextAlign {
+                x: TextAlignX::Center,
+                y: TextAlignY::Center,
+              })
+              .pivot(pivot)
+          };
+          self.font.set_font_size(layout.font(100)).unwrap();
+          pen.text(
+            &self.font,
+            &format!("{}", remaining.max(0.0).ceil()),
+            text_options(layout.client().center()),
+          );
+          self.font.set_font_size(layout.font(24)).unwrap();
+          pen.text(
+            &self.font,
+            "キーを押すとすぐに始まります",
+            text_options(layout.offset(
+              layout.client().center(),
+              0,
+              80,
+            )),
+          );
+        }
       }
 
-      let typed_key_buf_cloned = typed_key_buf.borrow().clone();
-      typed_key_buf.borrow_mut().clear();
-      mt_events =
-        self.model.key_press(typed_key_buf_cloned.into_iter());
+      // 打鍵の時刻は、いまの時計の時刻から打鍵後の経過時間を引いて求める
+      let now_ticks = Ticks::now(self.sdl).0;
+      let clock_time = self.model.clock_time();
+      let typed_keys: Vec<_> =
+        typed_key_buf.borrow_mut().drain(..).collect();
+      if self.model.is_started() {
+        mt_events = self.model.key_press_with_time(
+          typed_keys.into_iter().map(|(typed, timestamp)| {
+            let elapsed = now_ticks.saturating_sub(timestamp) as f64;
+            (typed, clock_time - (elapsed / 1e3).into())
+          }),
+        );
+      } else if skip_countdown.get()
+        || COUNTDOWN_SECONDS
+          <= countdown_start.elapsed().as_secs_f64()
+      {
+        // 準備中に押したキーは、始めるきっかけにだけ使う
+        mt_events = self.model.start();
+      }
 
       let draw_time = render_start_time.elapsed().as_secs_f64();
 
       delay((1e3 / 60.0 - draw_time * 1e3).max(0.0) as u32);
 
-      let new_time = game_start_time.elapsed().as_secs_f64();
-
-      mt_events.append(&mut self.model.set_time(new_time.into()));
+      if self.model.is_started() {
+        mt_events.append(&mut self.model.tick());
+      }
       print!("\rFPS: {}     ", 1.0 / draw_time);
 
       if ended
@@ -224,42 +406,9 @@ impl<'canvas> View for GameView<'canvas> {
         return Ok(ViewRoute::ResultView(
           self.model.activity().score().clone(),
           self.model.music_info(),
+          self.model.play_log().clone(),
         ));
       }
     }
   }
 }
-
-fn keycode_to_char(keycode: KeyCode) -> char {
-  use KeyCode::*;
-  match keycode {
-    A => 'a',
-    B => 'b',
-    C => 'c',
-    D => 'd',
-    E => 'e',
-    F => 'f',
-    G => 'g',
-    H => 'h',
-    I => 'i',
-    J => 'j',
-    K => 'k',
-    L => 'l',
-    M => 'm',
-    N => 'n',
-    O => 'o',
-    P => 'p',
-    Q => 'q',
-    R => 'r',
-    S => 's',
-    T => 't',
-    U => 'u',
-    V => 'v',
-    W => 'w',
-    X => 'x',
-    Y => 'y',
-    Z => 'z',
-    Minus => '-',
-    _ => '\0',
-  }
-}
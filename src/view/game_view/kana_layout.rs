@@ -0,0 +1,177 @@
+use rich_sdl2_rust::event::keyboard::scan_code::ScanCode;
+
+use crate::model::exp::sentence::kana::{DAKUTEN, HANDAKUTEN};
+
+// JIS 配列のキーの刻印と、仮名入力でそのキーに割り当てられた仮名、シフト時の仮名
+pub const JIS_KANA: &[(char, char, Option<char>)] = &[
+  ('1', 'ぬ', None),
+  ('2', 'ふ', None),
+  ('3', 'あ', Some('ぁ')),
+  ('4', 'う', Some('ぅ')),
+  ('5', 'え', Some('ぇ')),
+  ('6', 'お', Some('ぉ')),
+  ('7', 'や', Some('ゃ')),
+  ('8', 'ゆ', Some('ゅ')),
+  ('9', 'よ', Some('ょ')),
+  ('0', 'わ', Some('を')),
+  ('-', 'ほ', None),
+  ('^', 'へ', None),
+  ('¥', 'ー', None),
+  ('q', 'た', None),
+  ('w', 'て', None),
+  ('e', 'い', Some('ぃ')),
+  ('r', 'す', None),
+  ('t', 'か', None),
+  ('y', 'ん', None),
+  ('u', 'な', None),
+  ('i', 'に', None),
+  ('o', 'ら', None),
+  ('p', 'せ', None),
+  ('@', DAKUTEN, None),
+  ('[', HANDAKUTEN, None),
+  ('a', 'ち', None),
+  ('s', 'と', None),
+  ('d', 'し', None),
+  ('f', 'は', None),
+  ('g', 'き', None),
+  ('h', 'く', None),
+  ('j', 'ま', None),
+  ('k', 'の', None),
+  ('l', 'り', None),
+  (';', 'れ', None),
+  (':', 'け', None),
+  (']', 'む', None),
+  ('z', 'つ', Some('っ')),
+  ('x', 'さ', None),
+  ('c', 'そ', None),
+  ('v', 'ひ', None),
+  ('b', 'こ', None),
+  ('n', 'み', None),
+  ('m', 'も', None),
+  (',', 'ね', None),
+  ('.', 'る', None),
+  ('/', 'め', None),
+  ('\\', 'ろ', None),
+];
+
+pub fn key_to_kana(key: char, shifted: bool) -> Option<char> {
+  let &(_, kana, shifted_kana) =
+    JIS_KANA.iter().find(|&&(k, _, _)| k == key)?;
+  if shifted {
+    shifted_kana
+  } else {
+    Some(kana)
+  }
+}
+
+// 画面のキーボードに並ぶ、文字を打つキー
+pub const CHARACTER_SCAN_CODES: [ScanCode; 48] = {
+  use ScanCode::*;
+  [
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+    Num0,
+    Minus,
+    Equals,
+    International3,
+    LeftBracket,
+    RightBracket,
+    Semicolon,
+    Apostrophe,
+    Backslash,
+    Comma,
+    Period,
+    Slash,
+    International1,
+  ]
+};
+
+// 配列に依らない物理的なキーの位置から、JIS 配列での刻印を求める
+pub fn scan_code_to_key(scan_code: ScanCode) -> char {
+  use ScanCode::*;
+  match scan_code {
+    A => 'a',
+    B => 'b',
+    C => 'c',
+    D => 'd',
+    E => 'e',
+    F => 'f',
+    G => 'g',
+    H => 'h',
+    I => 'i',
+    J => 'j',
+    K => 'k',
+    L => 'l',
+    M => 'm',
+    N => 'n',
+    O => 'o',
+    P => 'p',
+    Q => 'q',
+    R => 'r',
+    S => 's',
+    T => 't',
+    U => 'u',
+    V => 'v',
+    W => 'w',
+    X => 'x',
+    Y => 'y',
+    Z => 'z',
+    Num1 => '1',
+    Num2 => '2',
+    Num3 => '3',
+    Num4 => '4',
+    Num5 => '5',
+    Num6 => '6',
+    Num7 => '7',
+    Num8 => '8',
+    Num9 => '9',
+    Num0 => '0',
+    Minus => '-',
+    Equals => '^',
+    International3 => '¥',
+    LeftBracket => '@',
+    RightBracket => '[',
+    Semicolon => ';',
+    Apostrophe => ':',
+    Backslash | NonUsHash => ']',
+    Comma => ',',
+    Period => '.',
+    Slash => '/',
+    International1 => '\\',
+    Space => ' ',
+    _ => '\0',
+  }
+}
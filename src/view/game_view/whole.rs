@@ -1,85 +1,125 @@
 use std::rc::Rc;
 
 use crate::{
-  model::exp::{
-    game_activity::GameScore, scoremap::MusicInfo, sentence::Sentence,
+  model::{
+    exp::{
+      game_activity::GameScore,
+      scoremap::MusicInfo,
+      sentence::{InputMethod, Sentence},
+      time::Seconds,
+    },
+    keyboard_layout::KeyboardLayout,
+    skin::Palette,
   },
   view::{
     components::{
       Header, HeaderProps, SentenceResult, Stats, StatsProps,
     },
+    layout::{Layout, Length},
     Component,
   },
 };
 
 mod finder;
 mod keyboard;
+mod spellings;
+mod timeline;
 
 use finder::{Finder, FinderProps};
 use keyboard::{Keyboard, KeyboardProps};
 use rich_sdl2_rust::ttf::font::Font;
-use rich_sdl2_rust::{
-  color::Rgb,
-  geo::{Point, Rect, Size},
-  renderer::pen::Pen,
-};
+use rich_sdl2_rust::{color::Rgb, geo::Rect, renderer::pen::Pen};
+use spellings::{Spellings, SpellingsProps};
+use timeline::{Timeline, TimelineProps};
 
 #[derive(PartialEq)]
 pub struct WholeProps {
   pub pressed_keys: Vec<char>,
   pub sentence: Sentence,
+  // これから始まる文と、始まるまでの時間
+  pub upcoming: Vec<(Seconds, Sentence)>,
   pub sentence_result: Option<SentenceResult>,
   pub music_info: MusicInfo,
   pub type_per_second: f64,
   pub score: GameScore,
   pub section_remaining_ratio: f64,
+  pub input_method: InputMethod,
+  pub spelling_hints: bool,
+  pub keyboard_layout: KeyboardLayout,
+  pub finger_guide: bool,
+}
+
+// 綴りの候補を出すときは、次に打てるキーをすべて光らせる
+fn hint_keys(props: &WholeProps) -> Vec<char> {
+  if props.spelling_hints {
+    return props.sentence.next_keys();
+  }
+  let keys = props.sentence.keys();
+  keys.will_input.chars().next().map_or(vec![], |c| vec![c])
+}
+
+fn alternatives(props: &WholeProps) -> Option<(String, Vec<String>)> {
+  props
+    .spelling_hints
+    .then(|| props.sentence.alternatives())
+    .flatten()
 }
 
 pub struct Whole<'font> {
   keyboard: Keyboard<'font>,
   finder: Finder<'font>,
+  timeline: Timeline<'font>,
+  spellings: Spellings<'font>,
   header: Header<'font>,
   stats: Stats<'font>,
-  client: Rect,
+  header_dim: Rect,
+  keyboard_dim: Rect,
+  palette: Palette,
 }
 
 impl<'font> Whole<'font> {
   pub fn new(
     props: WholeProps,
     font: Rc<Font<'font>>,
-    client: Rect,
+    layout: Layout,
+    palette: Palette,
   ) -> Self {
-    let hint = {
-      let roman = props.sentence.roman();
-      roman.will_input.chars().next().map_or(vec![], |c| vec![c])
-    };
-    let keyboard_dim = Rect {
-      up_left: Point {
-        x: 0,
-        y: client.size.height as i32 - 350,
-      },
-      size: Size {
-        width: client.size.width,
-        height: 200,
-      },
-    };
+    let hint = hint_keys(&props);
+    // 上から見出し・歌詞・先の歌詞・余白・キーボード・成績の順に積む。
+    // 余白はウィンドウの縦横比が基準と違うときの差を吸収する
+    let rows = layout.rows(
+      layout.client(),
+      &[
+        Length::Fixed(100),
+        Length::Fixed(150),
+        Length::Fixed(50),
+        Length::Flex(1),
+        Length::Fixed(160),
+        Length::Fixed(140),
+      ],
+    );
+    let (
+      header_dim,
+      finder_dim,
+      timeline_dim,
+      keyboard_dim,
+      stats_dim,
+    ) = (rows[0], rows[1], rows[2], rows[4], rows[5]);
 
     let keyboard = Keyboard::new(
       KeyboardProps {
         pressed_keys: props.pressed_keys.clone(),
         highlighted_keys: hint,
+        kana_legends: props.input_method == InputMethod::Kana,
+        key_layout: props.keyboard_layout,
+        finger_guide: props.finger_guide,
       },
       Rc::clone(&font),
       keyboard_dim,
+      layout,
+      palette,
     );
 
-    let finder_dim = Rect {
-      up_left: Point { x: 0, y: 100 },
-      size: Size {
-        width: client.size.width,
-        height: 150,
-      },
-    };
     let finder = Finder::new(
       FinderProps {
         sentence: props.sentence.clone(),
@@ -87,15 +127,34 @@ impl<'font> Whole<'font> {
       },
       Rc::clone(&font),
       finder_dim,
+      layout,
+      palette,
+    );
+
+    let timeline = Timeline::new(
+      TimelineProps {
+        upcoming: props.upcoming.clone(),
+      },
+      Rc::clone(&font),
+      timeline_dim,
+      layout,
+      palette,
     );
 
-    let header_dim = Rect {
-      up_left: Point { x: 0, y: 0 },
-      size: Size {
-        width: client.size.width,
-        height: 100,
+    // 綴りの候補は、キーボードの左の空いているところに出す
+    let spellings_dim = layout
+      .columns(keyboard_dim, &[Length::Fixed(130), Length::Flex(1)])
+      [0];
+    let spellings = Spellings::new(
+      SpellingsProps {
+        alternatives: alternatives(&props),
       },
-    };
+      Rc::clone(&font),
+      spellings_dim,
+      layout,
+      palette,
+    );
+
     let header = Header::new(
       HeaderProps {
         music_info: props.music_info.clone(),
@@ -104,18 +163,10 @@ impl<'font> Whole<'font> {
       },
       Rc::clone(&font),
       header_dim,
+      layout,
+      palette,
     );
 
-    let stats_dim = Rect {
-      up_left: Point {
-        x: 0,
-        y: client.size.height as i32 - 150,
-      },
-      size: Size {
-        width: client.size.width,
-        height: 150,
-      },
-    };
     let stats = Stats::new(
       StatsProps {
         type_per_second: props.type_per_second,
@@ -123,14 +174,20 @@ impl<'font> Whole<'font> {
       },
       Rc::clone(&font),
       stats_dim,
+      layout,
+      palette,
     );
 
     Self {
       keyboard,
       finder,
+      timeline,
+      spellings,
       header,
       stats,
-      client,
+      header_dim,
+      keyboard_dim,
+      palette,
     }
   }
 }
@@ -143,14 +200,14 @@ impl<'font> Component for Whole<'font> {
   }
 
   fn update(&mut self, props: Self::Props) {
-    let hint = {
-      let roman = props.sentence.roman();
-      roman.will_input.chars().next().map_or(vec![], |c| vec![c])
-    };
+    let hint = hint_keys(&props);
 
     self.keyboard.update(KeyboardProps {
       pressed_keys: props.pressed_keys.clone(),
       highlighted_keys: hint,
+      kana_legends: props.input_method == InputMethod::Kana,
+      key_layout: props.keyboard_layout,
+      finger_guide: props.finger_guide,
     });
 
     self.finder.update(FinderProps {
@@ -158,6 +215,14 @@ impl<'font> Component for Whole<'font> {
       remaining_ratio: props.section_remaining_ratio,
     });
 
+    self.spellings.update(SpellingsProps {
+      alternatives: alternatives(&props),
+    });
+
+    self.timeline.update(TimelineProps {
+      upcoming: props.upcoming,
+    });
+
     self.header.update(HeaderProps {
       music_info: props.music_info,
       score_point: props.score.score_point,
@@ -171,46 +236,20 @@ impl<'font> Component for Whole<'font> {
   }
 
   fn render(&self, pen: &Pen<'_>) {
-    let &Whole { client, .. } = &self;
-
-    pen.set_color(Rgb {
-      r: 253,
-      g: 243,
-      b: 226,
-    });
+    pen.set_color(self.palette.background.into());
     pen.clear();
 
-    {
-      let header_dim = Rect {
-        up_left: Point::default(),
-        size: Size {
-          width: client.size.width,
-          height: 100,
-        },
-      };
-      self.header.render(pen);
-      pen.set_color(0.into());
-      pen.stroke_rect(header_dim);
-    }
+    self.header.render(pen);
+    pen.set_color(self.palette.border.into());
+    pen.stroke_rect(self.header_dim);
 
     self.finder.render(pen);
+    self.timeline.render(pen);
 
-    {
-      let keyboard_dim = Rect {
-        up_left: Point {
-          x: 0,
-          y: client.size.height as i32 - 350,
-        },
-        size: Size {
-          width: client.size.width,
-          height: 200,
-        },
-      };
-      self.keyboard.render(pen);
-
-      pen.set_color(0.into());
-      pen.stroke_rect(keyboard_dim);
-    }
+    self.keyboard.render(pen);
+    self.spellings.render(pen);
+    pen.set_color(self.palette.border.into());
+    pen.stroke_rect(self.keyboard_dim);
 
     self.stats.render(pen);
   }
@@ -1,17 +1,19 @@
 use rich_sdl2_rust::ttf::font::{
   pen::{FontRenderExt, FontRenderOptions, TextAlign, TextAlignX},
-  Font, RenderMode, StyleExt,
+  Font, FontStyle, RenderMode, StyleExt,
 };
 use rich_sdl2_rust::{
-  color::Rgb,
   geo::{Point, Rect, Size},
   renderer::pen::Pen,
 };
 use std::rc::Rc;
 
 use crate::{
-  model::exp::sentence::{Sentence, TypingStr},
-  view::Component,
+  model::{
+    exp::sentence::{Sentence, TypingStr},
+    skin::Palette,
+  },
+  view::{layout::Layout, opaque, Component},
 };
 
 #[derive(PartialEq)]
@@ -23,6 +25,8 @@ pub struct FinderProps {
 pub struct Finder<'font> {
   props: FinderProps,
   client: Rect,
+  layout: Layout,
+  palette: Palette,
   font: Rc<Font<'font>>,
 }
 
@@ -31,17 +35,97 @@ impl<'font> Finder<'font> {
     mut initial_props: FinderProps,
     font: Rc<Font<'font>>,
     client: Rect,
+    layout: Layout,
+    palette: Palette,
   ) -> Self {
     initial_props.remaining_ratio =
       initial_props.remaining_ratio.max(0.).min(1.);
     Self {
       props: initial_props,
       client,
+      layout,
+      palette,
       font,
     }
   }
 }
 
+impl Finder<'_> {
+  // 歌詞を親文字ごとに並べ、ルビはその上に小さく書く。
+  // 読みを打ち終えた親文字は、ルビごと薄くする
+  fn render_lyrics(&self, pen: &Pen<'_>) {
+    let Finder {
+      props,
+      font,
+      client,
+      layout,
+      palette,
+    } = &self;
+    let pieces = props.sentence.ruby_pieces();
+    let has_ruby = pieces.iter().any(|piece| piece.reading.is_some());
+    let ruby_height = layout.font(14);
+    let japanese_height = layout.font(30);
+    let base_top = if has_ruby {
+      client.top() + ruby_height as i32
+    } else {
+      client.top()
+    };
+
+    let mut x = client.left();
+    for piece in pieces {
+      font.set_font_size(japanese_height).unwrap();
+      let base_width =
+        font.rendered_size(&piece.base).map_or(0, |size| size.width);
+      let color = if piece.typed {
+        palette.label
+      } else {
+        palette.sub_text
+      };
+      let mut slot_width = base_width;
+      if let Some(reading) = &piece.reading {
+        font.set_font_size(ruby_height).unwrap();
+        let ruby_width =
+          font.rendered_size(reading).map_or(0, |size| size.width);
+        slot_width = slot_width.max(ruby_width);
+        pen.text(
+          font,
+          reading,
+          FontRenderOptions::new()
+            .mode(RenderMode::Blended {
+              foreground: opaque(color),
+            })
+            .align(TextAlign {
+              x: TextAlignX::Center,
+              ..Default::default()
+            })
+            .pivot(Point {
+              x: x + slot_width as i32 / 2,
+              y: client.top(),
+            }),
+        );
+        font.set_font_size(japanese_height).unwrap();
+      }
+      pen.text(
+        font,
+        &piece.base,
+        FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(color),
+          })
+          .align(TextAlign {
+            x: TextAlignX::Center,
+            ..Default::default()
+          })
+          .pivot(Point {
+            x: x + slot_width as i32 / 2,
+            y: base_top,
+          }),
+      );
+      x += slot_width as i32;
+    }
+  }
+}
+
 impl<'font> Component for Finder<'font> {
   type Props = FinderProps;
 
@@ -58,26 +142,20 @@ impl<'font> Component for Finder<'font> {
       props,
       font,
       client,
+      layout,
+      palette,
     } = &self;
     let FinderProps {
       remaining_ratio,
       sentence,
     } = &props;
 
-    pen.set_color(Rgb {
-      r: 230,
-      g: 220,
-      b: 200,
-    });
+    pen.set_color(palette.lane.into());
     pen.fill_rect(*client);
 
     let remaining_width =
       (client.size.width as f64 * remaining_ratio) as u32;
-    pen.set_color(Rgb {
-      r: 203,
-      g: 193,
-      b: 176,
-    });
+    pen.set_color(palette.lane_remaining.into());
     pen.fill_rect(Rect {
       size: Size {
         width: remaining_width,
@@ -86,108 +164,107 @@ impl<'font> Component for Finder<'font> {
       ..*client
     });
 
-    const JAPANESE_HEIGHT: u32 = 30;
     let half_x = (client.size.width / 2) as i32;
-    let will_input_japanese = sentence.origin();
-    font.set_font_size(JAPANESE_HEIGHT).unwrap();
-    pen.text(
-      font,
-      will_input_japanese,
-      FontRenderOptions::new()
-        .mode(RenderMode::Blended {
-          foreground: 0x505050.into(),
-        })
-        .align(TextAlign {
-          x: TextAlignX::Left,
-          ..Default::default()
-        })
-        .pivot(client.up_left),
-    );
-
-    const ROMAN_HEIGHT: u32 = 40;
-    font.set_font_size(ROMAN_HEIGHT).unwrap();
+    self.render_lyrics(pen);
+
+    // 色のほかに、これから打つ文字には下線を引いて見分けられるようにする
+    let will_input_style = if palette.markers {
+      FontStyle::Underline
+    } else {
+      FontStyle::Normal
+    };
+
+    let roman_height = layout.font(40);
+    font.set_font_size(roman_height).unwrap();
     {
       let TypingStr {
         will_input,
         inputted,
-      } = sentence.roman();
+      } = sentence.keys();
 
+      font.set_font_style(will_input_style);
       pen.text(
         font,
         &will_input,
         FontRenderOptions::new()
           .mode(RenderMode::Blended {
-            foreground: 0.into(),
+            foreground: opaque(palette.text),
           })
           .align(TextAlign {
             x: TextAlignX::Left,
             ..Default::default()
           })
           .pivot(Point {
-            x: half_x + 5,
-            y: client.bottom() - ROMAN_HEIGHT as i32 - 20,
+            x: half_x + layout.px(5),
+            y: client.bottom() - roman_height as i32 - layout.px(20),
           }),
       );
 
+      font.set_font_style(FontStyle::Normal);
       pen.text(
         font,
         &inputted,
         FontRenderOptions::new()
           .mode(RenderMode::Blended {
-            foreground: 0x505050.into(),
+            foreground: opaque(palette.sub_text),
           })
           .align(TextAlign {
             x: TextAlignX::Right,
             ..Default::default()
           })
           .pivot(Point {
-            x: half_x - 5,
-            y: client.bottom() - ROMAN_HEIGHT as i32 - 20,
+            x: half_x - layout.px(5),
+            y: client.bottom() - roman_height as i32 - layout.px(20),
           }),
       );
     }
-    const YOMIGANA_HEIGHT: u32 = 80;
-    font.set_font_size(YOMIGANA_HEIGHT).unwrap();
+    let yomigana_height = layout.font(80);
+    font.set_font_size(yomigana_height).unwrap();
     {
       let TypingStr {
         will_input,
         inputted,
       } = sentence.yomiagana();
 
+      font.set_font_style(will_input_style);
       pen.text(
         font,
         &will_input,
         FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(palette.text),
+          })
           .align(TextAlign {
             x: TextAlignX::Left,
             ..Default::default()
           })
           .pivot(Point {
-            x: half_x + 5,
+            x: half_x + layout.px(5),
             y: client.bottom()
-              - ROMAN_HEIGHT as i32
-              - YOMIGANA_HEIGHT as i32
-              - 20,
+              - roman_height as i32
+              - yomigana_height as i32
+              - layout.px(20),
           }),
       );
 
+      font.set_font_style(FontStyle::Normal);
       pen.text(
         font,
         &inputted,
         FontRenderOptions::new()
           .mode(RenderMode::Blended {
-            foreground: 0x505050.into(),
+            foreground: opaque(palette.sub_text),
           })
           .align(TextAlign {
             x: TextAlignX::Right,
             ..Default::default()
           })
           .pivot(Point {
-            x: half_x - 5,
+            x: half_x - layout.px(5),
             y: client.bottom()
-              - ROMAN_HEIGHT as i32
-              - YOMIGANA_HEIGHT as i32
-              - 20,
+              - roman_height as i32
+              - yomigana_height as i32
+              - layout.px(20),
           }),
       );
     }
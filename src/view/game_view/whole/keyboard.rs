@@ -3,7 +3,7 @@ use rich_sdl2_rust::ttf::font::{
     FontRenderExt, FontRenderOptions, TextAlign, TextAlignX,
     TextAlignY,
   },
-  Font, RenderMode, StyleExt,
+  Font, FontStyle, RenderMode, StyleExt,
 };
 use rich_sdl2_rust::{
   color::{Rgb, Rgba},
@@ -12,74 +12,99 @@ use rich_sdl2_rust::{
 };
 use std::rc::Rc;
 
-use crate::view::Component;
-
-const BLUE: Rgb = Rgb {
-  r: 0x40,
-  g: 0x50,
-  b: 0xb4,
-};
-const ORANGE: Rgb = Rgb {
-  r: 0xd1,
-  g: 0x9a,
-  b: 0x1d,
-};
-const GREEN: Rgb = Rgb {
-  r: 0x14,
-  g: 0x4c,
-  b: 0x40,
-};
-const BACK: Rgb = Rgb {
-  r: 0xfd,
-  g: 0xf3,
-  b: 0xe2,
-};
-const BLACK: Rgb = Rgb {
-  r: 0,
-  g: 0x0,
-  b: 0x0,
-};
-const GRAY: Rgb = Rgb {
-  r: 0xc3,
-  g: 0xc3,
-  b: 0xbe,
+use crate::{
+  model::{
+    keyboard_layout::{Finger, KeyboardLayout},
+    skin::Palette,
+  },
+  view::{
+    game_view::kana_layout::JIS_KANA, layout::Layout, opaque,
+    Component,
+  },
 };
 
 struct KeyCell<'font> {
   font: Rc<Font<'font>>,
+  // キーの位置を JIS 配列での刻印で表したもの
   key: char,
+  // 選んだ配列でそのキーに刻まれている文字
+  legend: char,
+  kana: Option<(char, Option<char>)>,
+  finger: Finger,
+  show_kana: bool,
+  finger_guide: bool,
   is_highlighted: bool,
   is_pressed: bool,
   client: Rect,
+  layout: Layout,
+  palette: Palette,
 }
 
 impl PartialEq for KeyCell<'_> {
   fn eq(&self, other: &Self) -> bool {
     self.key == other.key
+      && self.legend == other.legend
+      && self.kana == other.kana
+      && self.finger == other.finger
+      && self.show_kana == other.show_kana
+      && self.finger_guide == other.finger_guide
       && self.is_highlighted == other.is_highlighted
       && self.is_pressed == other.is_pressed
       && self.client == other.client
+      && self.layout == other.layout
+      && self.palette == other.palette
   }
 }
 
 impl KeyCell<'_> {
+  // 仮名入力の案内では、シフト時の仮名もそのキーで打つものとして扱う
+  fn is_key_of(&self, c: char) -> bool {
+    self.legend == c.to_ascii_lowercase()
+      || self.show_kana
+        && self.kana.map_or(false, |(kana, shifted)| {
+          kana == c || shifted == Some(c)
+        })
+  }
+
+  fn apply_props(&mut self, props: &KeyboardProps) {
+    self.show_kana = props.kana_legends;
+    self.finger_guide = props.finger_guide;
+    self.is_highlighted =
+      props.highlighted_keys.iter().any(|&c| self.is_key_of(c));
+    self.is_pressed = props.pressed_keys.contains(&self.key);
+  }
+
   fn bg_color(&self) -> Rgb {
     if self.is_highlighted {
-      GREEN
+      self.palette.key_highlight.into()
     } else {
-      BACK
+      self.palette.key_back.into()
+    }
+  }
+
+  // 色のほかに、押したキーは太字、案内のキーは下線で見分けられるようにする
+  fn font_style(&self) -> FontStyle {
+    if !self.palette.markers {
+      FontStyle::Normal
+    } else if self.is_pressed {
+      FontStyle::Bold
+    } else if self.is_highlighted {
+      FontStyle::Underline
+    } else {
+      FontStyle::Normal
     }
   }
 
   fn text_color(&self) -> Rgb {
+    let palette = &self.palette;
     if self.is_pressed {
-      ORANGE
+      palette.key_pressed.into()
     } else if self.is_highlighted {
-      GRAY
+      palette.key_legend.into()
     } else if self.key == 'f' || self.key == 'j' {
-      BLUE
+      palette.key_home.into()
     } else {
-      BLACK
+      palette.text.into()
     }
   }
 }
@@ -99,24 +124,93 @@ impl Component for KeyCell<'_> {
     let border_dim = Rect::from_center(
       self.client.center(),
       Size {
-        width: self.client.size.width - 5,
-        height: self.client.size.height - 5,
+        width: self.client.size.width - self.layout.dim(5),
+        height: self.client.size.height - self.layout.dim(5),
       },
     );
     pen.set_color(self.bg_color());
     pen.fill_rect(border_dim);
-    pen.set_color(BLACK);
+    pen.set_color(self.palette.text.into());
     pen.stroke_rect(border_dim);
+    if self.palette.markers {
+      // 案内のキーは枠を太くし、押したキーは右上に四角い印を付ける
+      if self.is_highlighted {
+        let thickness = self.layout.dim(3).max(2);
+        pen.stroke_rects((1..thickness).map(|inset| {
+          Rect::from_center(
+            border_dim.center(),
+            Size {
+              width: border_dim.size.width.saturating_sub(inset * 2),
+              height: border_dim
+                .size
+                .height
+                .saturating_sub(inset * 2),
+            },
+          )
+        }));
+      }
+      if self.is_pressed {
+        let mark = self.layout.dim(8).max(3);
+        pen.set_color(self.palette.key_pressed.into());
+        pen.fill_rect(Rect {
+          up_left: Point {
+            x: border_dim.right() - mark as i32 - self.layout.px(3),
+            y: border_dim.top() + self.layout.px(3),
+          },
+          size: Size {
+            width: mark,
+            height: mark,
+          },
+        });
+      }
+    }
+
+    // 案内のキーの下端を、打つ指の色で塗る
+    if self.finger_guide && self.is_highlighted {
+      let height = (border_dim.size.height / 5).max(3);
+      pen.set_color(finger_color(self.finger).into());
+      pen.fill_rect(Rect {
+        up_left: Point {
+          x: border_dim.left(),
+          y: border_dim.bottom() - height as i32,
+        },
+        size: Size {
+          width: border_dim.size.width,
+          height,
+        },
+      });
+    }
 
     let text_color = self.text_color();
+    let legend = match self.kana {
+      Some((kana, _)) if self.show_kana => {
+        self.font.set_font_size(self.layout.font(12)).unwrap();
+        pen.text(
+          &self.font,
+          &self.legend.to_string().to_uppercase(),
+          FontRenderOptions::new()
+            .mode(RenderMode::Blended {
+              foreground: opaque(self.palette.key_legend),
+            })
+            .pivot(Point {
+              x: border_dim.left() + self.layout.px(4),
+              y: border_dim.top() + self.layout.px(2),
+            }),
+        );
+        self.font.set_font_size(self.layout.font(20)).unwrap();
+        kana.to_string()
+      }
+      _ => self.legend.to_string().to_uppercase(),
+    };
+    self.font.set_font_style(self.font_style());
     pen.text(
       &self.font,
-      &self.key.to_string().to_uppercase(),
+      &legend,
       FontRenderOptions::new()
         .mode(RenderMode::Blended {
           foreground: Rgba {
             r: text_color.r,
-            g: text_color.b,
+            g: text_color.g,
             b: text_color.b,
             a: 255,
           },
@@ -127,6 +221,7 @@ impl Component for KeyCell<'_> {
         })
         .pivot(self.client.center()),
     );
+    self.font.set_font_style(FontStyle::Normal);
   }
 }
 
@@ -134,6 +229,9 @@ impl Component for KeyCell<'_> {
 pub struct KeyboardProps {
   pub pressed_keys: Vec<char>,
   pub highlighted_keys: Vec<char>,
+  pub kana_legends: bool,
+  pub key_layout: KeyboardLayout,
+  pub finger_guide: bool,
 }
 
 pub struct Keyboard<'font> {
@@ -141,6 +239,9 @@ pub struct Keyboard<'font> {
   cells: Vec<KeyCell<'font>>,
 
   font: Rc<Font<'font>>,
+  client: Rect,
+  layout: Layout,
+  palette: Palette,
 }
 
 impl<'font> Keyboard<'font> {
@@ -148,30 +249,50 @@ impl<'font> Keyboard<'font> {
     initial_props: KeyboardProps,
     font: Rc<Font<'font>>,
     client: Rect,
+    layout: Layout,
+    palette: Palette,
   ) -> Self {
+    let cells =
+      Self::cells(&initial_props, &font, client, layout, palette);
+    Self {
+      cells,
+      props: initial_props,
+      font,
+      client,
+      layout,
+      palette,
+    }
+  }
+
+  // 配列の段ごとのずれに合わせてキーを並べ、いちばん長い段が中央に来るようにする
+  fn cells(
+    props: &KeyboardProps,
+    font: &Rc<Font<'font>>,
+    client: Rect,
+    layout: Layout,
+    palette: Palette,
+  ) -> Vec<KeyCell<'font>> {
     const CELL_ASPECT: f64 = 1.0;
-    const KEY_CHARS_ROWS: &[&str] = &[
-      "1234567890-^¥",
-      "qwertyuiop@[",
-      "asdfghjkl;:]",
-      "zxcvbnm,./\\",
-    ];
+    let rows = props.key_layout.rows();
 
-    let cell_height =
-      client.size.height as f64 / KEY_CHARS_ROWS.len() as f64;
+    let cell_height = client.size.height as f64 / rows.len() as f64;
     let cell_width = cell_height * CELL_ASPECT;
+    let widest = rows
+      .iter()
+      .map(|row| row.offset + row.positions.chars().count() as f64)
+      .fold(0.0, f64::max);
+    let margin = client.size.width as f64 - widest * cell_width;
 
     let mut cells = vec![];
-
-    for (y, key_chars_row) in KEY_CHARS_ROWS.iter().enumerate() {
+    for (y, row) in rows.iter().enumerate() {
       let y = y as f64;
-      let row_amount = key_chars_row.len() as f64;
-      let margin = client.size.width as f64 - row_amount * cell_width;
-      for (x, key_char) in key_chars_row.chars().enumerate() {
-        let x = x as f64 + 1.0;
+      for (x, (position, legend)) in
+        row.positions.chars().zip(row.legends.chars()).enumerate()
+      {
         let center = Point {
-          x: (x * cell_width + client.left() as f64 + margin / 2.0)
-            as i32,
+          x: ((row.offset + x as f64 + 0.5) * cell_width
+            + client.left() as f64
+            + margin / 2.0) as i32,
           y: (y * cell_height
             + client.top() as f64
             + cell_height / 2.0) as i32,
@@ -183,26 +304,88 @@ impl<'font> Keyboard<'font> {
             height: cell_height as u32,
           },
         );
-        cells.push(KeyCell {
-          font: Rc::clone(&font),
-          key: key_char,
-          is_highlighted: initial_props
-            .highlighted_keys
-            .contains(&key_char),
-          is_pressed: initial_props.pressed_keys.contains(&key_char),
+        let mut cell = KeyCell {
+          font: Rc::clone(font),
+          key: position,
+          legend,
+          kana: JIS_KANA
+            .iter()
+            .find(|&&(key, _, _)| key == position)
+            .map(|&(_, kana, shifted)| (kana, shifted)),
+          finger: Finger::of_column(x),
+          show_kana: false,
+          finger_guide: false,
+          is_highlighted: false,
+          is_pressed: false,
           client: key_cell_client,
-        });
+          layout,
+          palette,
+        };
+        cell.apply_props(props);
+        cells.push(cell);
       }
     }
+    cells
+  }
 
-    Self {
-      cells,
-      props: initial_props,
+  // 指の色の見本を、キーボードの右の空いているところに並べる
+  fn render_finger_legend(&self, pen: &Pen<'_>) {
+    const FINGERS: [(Finger, &str); 4] = [
+      (Finger::Index, "人差し指"),
+      (Finger::Middle, "中指"),
+      (Finger::Ring, "薬指"),
+      (Finger::Little, "小指"),
+    ];
+    let Keyboard {
       font,
+      client,
+      layout,
+      palette,
+      ..
+    } = self;
+    let mark = layout.dim(12);
+    let left = client.right() - layout.px(120);
+    font.set_font_size(layout.font(14)).unwrap();
+    for (index, (finger, name)) in FINGERS.iter().enumerate() {
+      let top = client.top() + layout.px(20 + index as i32 * 30);
+      pen.set_color(finger_color(*finger).into());
+      pen.fill_rect(Rect {
+        up_left: Point { x: left, y: top },
+        size: Size {
+          width: mark,
+          height: mark,
+        },
+      });
+      pen.text(
+        font,
+        name,
+        FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(palette.text),
+          })
+          .align(TextAlign {
+            x: TextAlignX::Left,
+            y: TextAlignY::Center,
+          })
+          .pivot(Point {
+            x: left + mark as i32 + layout.px(6),
+            y: top + mark as i32 / 2,
+          }),
+      );
     }
   }
 }
 
+// 色覚の型によらず見分けやすいよう、Okabe-Ito の配色から選ぶ
+fn finger_color(finger: Finger) -> u32 {
+  match finger {
+    Finger::Index => 0xe69f00,
+    Finger::Middle => 0x56b4e9,
+    Finger::Ring => 0x009e73,
+    Finger::Little => 0xcc79a7,
+  }
+}
+
 impl<'font> Component for Keyboard<'font> {
   type Props = KeyboardProps;
 
@@ -211,13 +394,28 @@ impl<'font> Component for Keyboard<'font> {
   }
 
   fn update(&mut self, new_props: KeyboardProps) {
+    if self.props.key_layout != new_props.key_layout {
+      self.cells = Self::cells(
+        &new_props,
+        &self.font,
+        self.client,
+        self.layout,
+        self.palette,
+      );
+    }
+    for cell in self.cells.iter_mut() {
+      cell.apply_props(&new_props);
+    }
     self.props = new_props;
   }
 
   fn render(&self, ctx: &Pen<'_>) {
-    self.font.set_font_size(20).unwrap();
+    self.font.set_font_size(self.layout.font(20)).unwrap();
     for cell in &self.cells {
       cell.render(ctx);
     }
+    if self.props.finger_guide {
+      self.render_finger_legend(ctx);
+    }
   }
 }
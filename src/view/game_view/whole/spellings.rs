@@ -0,0 +1,113 @@
+use rich_sdl2_rust::ttf::font::{
+  pen::{FontRenderExt, FontRenderOptions, TextAlign, TextAlignX},
+  Font, RenderMode, StyleExt,
+};
+use rich_sdl2_rust::{
+  geo::{Point, Rect},
+  renderer::pen::Pen,
+};
+use std::rc::Rc;
+
+use crate::{
+  model::skin::Palette,
+  view::{layout::Layout, opaque, Component},
+};
+
+// これより多い候補は省いて「…」で示す
+const MAX_SPELLINGS: usize = 4;
+
+#[derive(PartialEq)]
+pub struct SpellingsProps {
+  // いま打っている仮名と、それを打てる綴りの候補
+  pub alternatives: Option<(String, Vec<String>)>,
+}
+
+pub struct Spellings<'font> {
+  props: SpellingsProps,
+  client: Rect,
+  layout: Layout,
+  palette: Palette,
+  font: Rc<Font<'font>>,
+}
+
+impl<'font> Spellings<'font> {
+  pub fn new(
+    props: SpellingsProps,
+    font: Rc<Font<'font>>,
+    client: Rect,
+    layout: Layout,
+    palette: Palette,
+  ) -> Self {
+    Self {
+      props,
+      client,
+      layout,
+      palette,
+      font,
+    }
+  }
+}
+
+impl<'font> Component for Spellings<'font> {
+  type Props = SpellingsProps;
+
+  fn is_needed_redraw(&self, new_props: &Self::Props) -> bool {
+    &self.props != new_props
+  }
+
+  fn update(&mut self, new_props: Self::Props) {
+    self.props = new_props;
+  }
+
+  fn render(&self, pen: &Pen<'_>) {
+    let Spellings {
+      props,
+      client,
+      layout,
+      palette,
+      font,
+    } = &self;
+    let (kana, styles) = match &props.alternatives {
+      Some(alternatives) => alternatives,
+      None => return,
+    };
+    let center_x = client.center().x;
+    let options = |color: u32, y: i32| {
+      FontRenderOptions::new()
+        .mode(RenderMode::Blended {
+          foreground: opaque(color),
+        })
+        .align(TextAlign {
+          x: TextAlignX::Center,
+          ..Default::default()
+        })
+        .pivot(Point {
+          x: center_x,
+          y: client.top() + layout.px(y),
+        })
+    };
+
+    font.set_font_size(layout.font(36)).unwrap();
+    pen.text(font, kana, options(palette.text, 8));
+
+    // 先頭の綴りは案内に出しているものなので、目立たせる
+    font.set_font_size(layout.font(18)).unwrap();
+    for (index, style) in
+      styles.iter().take(MAX_SPELLINGS).enumerate()
+    {
+      let color = if index == 0 {
+        palette.accent
+      } else {
+        palette.sub_text
+      };
+      pen.text(font, style, options(color, 56 + index as i32 * 22));
+    }
+    if MAX_SPELLINGS < styles.len() {
+      pen.text(
+        font,
+        "…",
+        options(palette.label, 56 + MAX_SPELLINGS as i32 * 22),
+      );
+    }
+  }
+}
@@ -0,0 +1,146 @@
+use rich_sdl2_rust::ttf::font::{
+  pen::{FontRenderExt, FontRenderOptions},
+  Font, RenderMode, StyleExt,
+};
+use rich_sdl2_rust::{
+  geo::{Point, Rect, Size},
+  renderer::pen::Pen,
+};
+use std::rc::Rc;
+
+use crate::{
+  model::{
+    exp::{sentence::Sentence, time::Seconds},
+    skin::Palette,
+  },
+  view::{layout::Layout, opaque, Component},
+};
+
+// この秒数先までを帯の幅に収める
+const TIMELINE_SPAN: f64 = 6.0;
+// 歌詞の文字を置く余地として、帯の右側は空けておく
+const TEXT_ROOM_RATIO: f64 = 0.7;
+
+#[derive(PartialEq)]
+pub struct TimelineProps {
+  // 始まるまでの時間と、その文
+  pub upcoming: Vec<(Seconds, Sentence)>,
+}
+
+pub struct Timeline<'font> {
+  props: TimelineProps,
+  client: Rect,
+  layout: Layout,
+  palette: Palette,
+  font: Rc<Font<'font>>,
+}
+
+impl<'font> Timeline<'font> {
+  pub fn new(
+    props: TimelineProps,
+    font: Rc<Font<'font>>,
+    client: Rect,
+    layout: Layout,
+    palette: Palette,
+  ) -> Self {
+    Self {
+      props,
+      client,
+      layout,
+      palette,
+      font,
+    }
+  }
+
+  // 始まるまでの時間が短いほど左に寄る
+  fn start_x(&self, until: Seconds) -> i32 {
+    let ratio = (until.as_f64() / TIMELINE_SPAN).max(0.).min(1.);
+    self.client.left()
+      + (self.client.size.width as f64 * TEXT_ROOM_RATIO * ratio)
+        as i32
+  }
+}
+
+impl<'font> Component for Timeline<'font> {
+  type Props = TimelineProps;
+
+  fn is_needed_redraw(&self, new_props: &Self::Props) -> bool {
+    &self.props != new_props
+  }
+
+  fn update(&mut self, new_props: Self::Props) {
+    self.props = new_props;
+  }
+
+  fn render(&self, pen: &Pen<'_>) {
+    let Timeline {
+      props,
+      client,
+      layout,
+      palette,
+      font,
+    } = &self;
+
+    pen.set_color(palette.lane.into());
+    pen.fill_rect(*client);
+
+    // 遠い文から描いて、近い文の帯で重なった部分を隠す
+    for (index, (until, sentence)) in
+      props.upcoming.iter().enumerate().rev()
+    {
+      let x = self.start_x(*until);
+      let block = Rect {
+        up_left: Point { x, y: client.top() },
+        size: Size {
+          width: (client.right() - x).max(0) as u32,
+          height: client.size.height,
+        },
+      };
+      pen.set_color(if index % 2 == 0 {
+        palette.lane_remaining.into()
+      } else {
+        palette.lane.into()
+      });
+      pen.fill_rect(block);
+      pen.set_color(palette.border.into());
+      pen.fill_rect(Rect {
+        size: Size {
+          width: layout.dim(2).max(1),
+          ..block.size
+        },
+        ..block
+      });
+
+      font.set_font_size(layout.font(12)).unwrap();
+      pen.text(
+        font,
+        &format!("{:.1}s", until.as_f64().max(0.)),
+        FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(palette.label),
+          })
+          .pivot(layout.offset(block.up_left, 6, 2)),
+      );
+      font.set_font_size(layout.font(18)).unwrap();
+      pen.text(
+        font,
+        sentence.origin(),
+        FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(palette.text),
+          })
+          .pivot(layout.offset(block.up_left, 50, 2)),
+      );
+      font.set_font_size(layout.font(14)).unwrap();
+      pen.text(
+        font,
+        &sentence.yomiagana().will_input,
+        FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(palette.sub_text),
+          })
+          .pivot(layout.offset(block.up_left, 50, 26)),
+      );
+    }
+  }
+}
@@ -0,0 +1,217 @@
+use rich_sdl2_rust::{
+  geo::{Point, Rect, Size},
+  renderer::Renderer,
+};
+
+// 画面はこの大きさを基準に作り、実際のウィンドウに合わせて拡大・縮小する
+pub const BASE_SIZE: Size = Size {
+  width: 800,
+  height: 600,
+};
+
+// 行や列に割り当てる長さ。Fixed は基準の大きさでのピクセル数、
+// Flex は残りを比で分け合うときの重み
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+  Fixed(u32),
+  Flex(u32),
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+  TopLeft,
+  Top,
+  TopRight,
+  Left,
+  Center,
+  Right,
+  BottomLeft,
+  Bottom,
+  BottomRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+  client: Rect,
+  scale: f64,
+}
+
+impl Layout {
+  pub fn new(client: Rect) -> Self {
+    let scale = (client.size.width as f64 / BASE_SIZE.width as f64)
+      .min(client.size.height as f64 / BASE_SIZE.height as f64);
+    Self { client, scale }
+  }
+
+  // ウィンドウ全体を使うレイアウト
+  pub fn of(renderer: &Renderer<'_>) -> Self {
+    Self::new(Rect {
+      up_left: Point::default(),
+      size: renderer.output_size().unwrap(),
+    })
+  }
+
+  // ウィンドウの大きさが変わっていれば、作り直したレイアウトを返す
+  pub fn resized(&self, renderer: &Renderer<'_>) -> Option<Self> {
+    let size = renderer.output_size().ok()?;
+    (size != self.client.size).then(|| Self::of(renderer))
+  }
+
+  pub fn client(&self) -> Rect {
+    self.client
+  }
+
+  // 基準の大きさでの長さを、いまのウィンドウでの長さにする
+  pub fn px(&self, length: i32) -> i32 {
+    (length as f64 * self.scale).round() as i32
+  }
+
+  pub fn dim(&self, length: u32) -> u32 {
+    (length as f64 * self.scale).round() as u32
+  }
+
+  pub fn font(&self, points: u32) -> u32 {
+    self.dim(points).max(1)
+  }
+
+  pub fn offset(&self, point: Point, x: i32, y: i32) -> Point {
+    point.offset(self.px(x), self.px(y))
+  }
+
+  pub fn rows(&self, area: Rect, lengths: &[Length]) -> Vec<Rect> {
+    self
+      .split(area.size.height, lengths)
+      .into_iter()
+      .map(|(start, height)| Rect {
+        up_left: area.up_left.offset(0, start as i32),
+        size: Size {
+          width: area.size.width,
+          height,
+        },
+      })
+      .collect()
+  }
+
+  pub fn columns(&self, area: Rect, lengths: &[Length]) -> Vec<Rect> {
+    self
+      .split(area.size.width, lengths)
+      .into_iter()
+      .map(|(start, width)| Rect {
+        up_left: area.up_left.offset(start as i32, 0),
+        size: Size {
+          width,
+          height: area.size.height,
+        },
+      })
+      .collect()
+  }
+
+  // 固定の長さを先に取り、残りを Flex の重みで分ける
+  fn split(&self, total: u32, lengths: &[Length]) -> Vec<(u32, u32)> {
+    let fixed: u32 = lengths
+      .iter()
+      .map(|length| match length {
+        Length::Fixed(length) => self.dim(*length),
+        Length::Flex(_) => 0,
+      })
+      .sum();
+    let weights: u32 = lengths
+      .iter()
+      .map(|length| match length {
+        Length::Fixed(_) => 0,
+        Length::Flex(weight) => *weight,
+      })
+      .sum();
+    let rest = total.saturating_sub(fixed);
+    let mut start = 0;
+    lengths
+      .iter()
+      .map(|length| {
+        let size = match length {
+          Length::Fixed(length) => self.dim(*length),
+          Length::Flex(weight) if 0 < weights => {
+            (rest as u64 * *weight as u64 / weights as u64) as u32
+          }
+          Length::Flex(_) => 0,
+        };
+        let size = size.min(total.saturating_sub(start));
+        let placed = (start, size);
+        start += size;
+        placed
+      })
+      .collect()
+  }
+
+  // 基準の大きさで width x height の領域を、area の中の anchor の位置に置く
+  pub fn anchor(
+    &self,
+    area: Rect,
+    width: u32,
+    height: u32,
+    anchor: Anchor,
+    margin: u32,
+  ) -> Rect {
+    let size = Size {
+      width: self.dim(width),
+      height: self.dim(height),
+    };
+    let margin = self.dim(margin) as i32;
+    let left = area.left() + margin;
+    let right = area.right() - margin - size.width as i32;
+    let center_x = area.center().x - size.width as i32 / 2;
+    let top = area.top() + margin;
+    let bottom = area.bottom() - margin - size.height as i32;
+    let center_y = area.center().y - size.height as i32 / 2;
+    use Anchor::*;
+    let (x, y) = match anchor {
+      TopLeft => (left, top),
+      Top => (center_x, top),
+      TopRight => (right, top),
+      Left => (left, center_y),
+      Center => (center_x, center_y),
+      Right => (right, center_y),
+      BottomLeft => (left, bottom),
+      Bottom => (center_x, bottom),
+      BottomRight => (right, bottom),
+    };
+    Rect {
+      up_left: Point { x, y },
+      size,
+    }
+  }
+}
+
+#[test]
+fn layout() {
+  let layout = Layout::new(Rect {
+    up_left: Point::default(),
+    size: Size {
+      width: 1600,
+      height: 1200,
+    },
+  });
+  assert_eq!(20, layout.px(10));
+  assert_eq!(40, layout.font(20));
+
+  let rows = layout.rows(
+    layout.client(),
+    &[Length::Fixed(100), Length::Flex(1), Length::Flex(3)],
+  );
+  assert_eq!((0, 200), (rows[0].top(), rows[0].size.height));
+  assert_eq!((200, 250), (rows[1].top(), rows[1].size.height));
+  assert_eq!((450, 750), (rows[2].top(), rows[2].size.height));
+
+  let button =
+    layout.anchor(layout.client(), 240, 80, Anchor::BottomRight, 20);
+  assert_eq!(
+    Rect {
+      up_left: Point { x: 1080, y: 1000 },
+      size: Size {
+        width: 480,
+        height: 160,
+      },
+    },
+    button
+  );
+}
@@ -4,9 +4,21 @@ use rich_sdl2_rust::mixer::{
   music::MixMusic,
 };
 use rich_sdl2_rust::SdlError;
-use std::{collections::HashMap, path::Path};
+use std::{
+  cell::Cell,
+  collections::{BTreeMap, HashMap},
+  path::PathBuf,
+  rc::Rc,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+  },
+  time::Instant,
+};
 use PlayerError::*;
 
+use crate::model::{exp::time::Seconds, game::Clock, skin::Skin};
+
 pub enum SEKind {
   Correct,
   Fail,
@@ -15,6 +27,7 @@ pub enum SEKind {
   MissedSentence,
   PerfectSentence,
   PerfectSection,
+  Click,
 }
 
 #[derive(Debug)]
@@ -31,20 +44,107 @@ impl From<std::io::Error> for PlayerError {
 
 type Chunks<'a> = HashMap<String, MixChunk<'a>>;
 
+const MAX_VOLUME: u32 = 128;
+
+// ミキサーが書き出した音声の量。音声のスレッドから書き込まれる
+#[derive(Default)]
+struct MixProgress {
+  frames: AtomicU64,
+  // 最後に書き出したときの時刻とフレーム数
+  last_mix: Mutex<Option<(Instant, u64)>>,
+}
+
+impl MixProgress {
+  fn record(&self, frames: u64) {
+    self.frames.fetch_add(frames, Ordering::AcqRel);
+    *self.last_mix.lock().unwrap() = Some((Instant::now(), frames));
+  }
+}
+
+// 曲の再生位置を示す時計。曲を流し始めてからミキサーが書き出したフレーム数で測るので、
+// 音声が途切れたり遅れたりしても曲に合わせて進む
+#[derive(Clone)]
+pub struct MusicClock {
+  progress: Arc<MixProgress>,
+  // 曲を流し始めたときのフレーム数
+  started: Rc<Cell<Option<u64>>>,
+  frequency: f64,
+}
+
+impl Clock for MusicClock {
+  fn now(&self) -> Seconds {
+    let started = match self.started.get() {
+      Some(started) => started,
+      None => return 0.0.into(),
+    };
+    let frames = self.progress.frames.load(Ordering::Acquire);
+    if frames <= started {
+      return 0.0.into();
+    }
+    // 書き出しはバッファごとにまとめて進むので、その間は実時間で補う。
+    // 補う量は直前のバッファの長さまでにして、時刻が戻らないようにする
+    let between = self.progress.last_mix.lock().unwrap().map_or(
+      0.0,
+      |(mixed_at, buffer)| {
+        mixed_at
+          .elapsed()
+          .as_secs_f64()
+          .min(buffer as f64 / self.frequency)
+      },
+    );
+    ((frames - started) as f64 / self.frequency + between).into()
+  }
+}
+
 pub struct Player<'music> {
   device: &'music MixDevice<'music>,
   music: Option<MixMusic<'music>>,
   chunks: Chunks<'music>,
   group: ChannelGroup<'music>,
+  progress: Arc<MixProgress>,
+  started: Rc<Cell<Option<u64>>>,
+  frequency: f64,
+  volume: u32,
 }
 
 impl<'music> Player<'music> {
-  pub fn new(device: &'music MixDevice) -> Self {
-    Self {
+  pub fn new(
+    device: &'music MixDevice,
+    skin: &Skin,
+  ) -> Result<Self, PlayerError> {
+    let chunks = load_chunks(device, &skin.sounds)?;
+    // 全チャンネルを混ぜた後の音声が渡されるので、その長さを数える
+    let spec = device.query();
+    let frame_bytes = (spec.channels * spec.format.bit_size as u32
+      / 8)
+      .max(1) as usize;
+    let progress = Arc::new(MixProgress::default());
+    let writer = Arc::clone(&progress);
+    device.attach_post_effect(Box::new(
+      move |stream: &mut [u8]| {
+        writer.record((stream.len() / frame_bytes) as u64);
+      },
+    ));
+    Ok(Self {
       device,
       music: None,
-      chunks: load_chunks(device).expect("missing audio file dir"),
+      chunks,
       group: ChannelGroup::new(device, 40),
+      progress,
+      started: Rc::new(Cell::new(None)),
+      frequency: spec.frequency.max(1) as f64,
+      volume: MAX_VOLUME,
+    })
+  }
+
+  // 音量を 0 から 100 までの百分率で設定する
+  pub fn set_volume(&mut self, percent: u32) {
+    self.volume = MAX_VOLUME * percent.min(100) / 100;
+    for chunk in self.chunks.values() {
+      chunk.set_volume(self.volume);
+    }
+    if let Some(ref music) = self.music {
+      music.set_volume(self.volume);
     }
   }
 
@@ -55,6 +155,7 @@ impl<'music> Player<'music> {
     let bgm_file_path = format!("score/{}", bgm_name);
     let music = MixMusic::new(self.device, &bgm_file_path)
       .map_err(AudioError)?;
+    music.set_volume(self.volume);
     self.music = Some(music);
     self.play_bgm()?;
     Ok(())
@@ -63,10 +164,22 @@ impl<'music> Player<'music> {
   pub fn play_bgm(&self) -> Result<(), PlayerError> {
     if let Some(ref music) = self.music {
       music.play(Some(1)).map_err(AudioError)?;
+      // 曲は次に書き出すバッファから混ざる
+      self
+        .started
+        .set(Some(self.progress.frames.load(Ordering::Acquire)));
     }
     Ok(())
   }
 
+  pub fn music_clock(&self) -> MusicClock {
+    MusicClock {
+      progress: Arc::clone(&self.progress),
+      started: Rc::clone(&self.started),
+      frequency: self.frequency,
+    }
+  }
+
   pub fn stop_bgm(&self, fade_time: u32) -> Result<(), PlayerError> {
     if let Some(ref music) = self.music {
       music.fade_out(fade_time).map_err(AudioError)?;
@@ -91,6 +204,7 @@ impl<'music> Player<'music> {
 
   pub fn play_se(&self, kind: SEKind) -> Result<(), PlayerError> {
     use SEKind::*;
+    // スキンの目録に書く効果音の名前
     match kind {
       Correct => self.play_se_file("correct"),
       Fail => self.play_se_file("fail"),
@@ -99,33 +213,35 @@ impl<'music> Player<'music> {
       MissedSentence => self.play_se_file("missed"),
       PerfectSentence => self.play_se_file("perfect_sentence"),
       PerfectSection => self.play_se_file("perfect_section"),
+      Click => self.play_se_file("click"),
     }
   }
 }
 
+impl Drop for Player<'_> {
+  fn drop(&mut self) {
+    self.device.detach_all_post_effect();
+  }
+}
+
+// 読み込めない効果音は、組み込みのスキンの音で代わりにする
 fn load_chunks<'music>(
   device: &'music MixDevice,
+  sounds: &BTreeMap<String, PathBuf>,
 ) -> Result<Chunks<'music>, PlayerError> {
-  let path = Path::new("asset/");
+  let defaults = Skin::default().sounds;
+  let load = |path: &PathBuf| {
+    MixChunk::new(device, &path.to_string_lossy()).map_err(AudioError)
+  };
   let mut chunks: Chunks = HashMap::new();
-  for entry in path.read_dir()? {
-    let file = entry?;
-    if file.path().extension().map_or(false, |ext| ext == "wav") {
-      chunks.insert(
-        file.path().file_stem().map_or("".into(), |name| {
-          name.to_string_lossy().to_string()
-        }),
-        {
-          let chunk = MixChunk::new(
-            device,
-            file.path().to_str().expect("invalid chunk path"),
-          )
-          .map_err(AudioError)?;
-          chunk.set_volume(112);
-          chunk
-        },
-      );
-    }
+  for (name, path) in sounds {
+    let chunk = match (load(path), defaults.get(name)) {
+      (Ok(chunk), _) => chunk,
+      (Err(_), Some(default)) if default != path => load(default)?,
+      (Err(err), _) => return Err(err),
+    };
+    chunk.set_volume(112);
+    chunks.insert(name.clone(), chunk);
   }
   Ok(chunks)
 }
@@ -6,14 +6,15 @@ use rich_sdl2_rust::ttf::font::{
   Font, RenderMode, StyleExt,
 };
 use rich_sdl2_rust::{
-  color::{Rgb, Rgba},
   delay,
-  geo::{Point, Rect, Size},
+  event::keyboard::scan_code::ScanCode,
+  geo::{Point, Rect},
   renderer::{pen::Pen, Renderer},
   EventBox, Video,
 };
 use std::{
   cell::{Cell, RefCell},
+  path::PathBuf,
   rc::Rc,
   time::Instant,
 };
@@ -25,16 +26,32 @@ use super::{
   View, ViewRoute,
 };
 use crate::{
-  model::exp::{game_activity::GameScore, scoremap::MusicInfo},
-  view::Component,
+  model::{
+    exp::{game_activity::GameScore, scoremap::MusicInfo},
+    game::{
+      export::{default_stem, ResultExport},
+      PlayLog,
+    },
+    skin::Skin,
+  },
+  view::{
+    layout::{Anchor, Layout, Length},
+    opaque, Component,
+  },
 };
+use breakdown::{Breakdown, BreakdownProps, Tab};
+
+mod breakdown;
 
 pub struct ResultView<'view> {
   renderer: &'view Renderer<'view>,
   score: GameScore,
   music_info: MusicInfo,
+  log: PlayLog,
+  export_dir: PathBuf,
   font: Rc<Font<'view>>,
   video: &'view Video<'view>,
+  skin: Skin,
 }
 
 impl<'view> ResultView<'view> {
@@ -42,161 +59,335 @@ impl<'view> ResultView<'view> {
     renderer: &'view Renderer<'view>,
     score: GameScore,
     music_info: MusicInfo,
+    log: PlayLog,
+    export_dir: PathBuf,
     font: Rc<Font<'view>>,
     video: &'view Video<'view>,
+    skin: Skin,
   ) -> Self {
     Self {
       renderer,
       score,
       music_info,
+      log,
+      export_dir,
       font,
       video,
+      skin,
+    }
+  }
+}
+
+impl<'view> ResultView<'view> {
+  // 書き出した結果を、画面に出す一文で返す
+  fn export(&self) -> String {
+    let export =
+      ResultExport::new(&self.music_info, &self.score, &self.log);
+    match export.write_to(&self.export_dir, &default_stem()) {
+      Ok(_) => {
+        format!("{} に書き出しました", self.export_dir.display())
+      }
+      Err(err) => format!("書き出せませんでした: {}", err),
     }
   }
 }
 
 impl<'view> View for ResultView<'view> {
   fn run(&mut self) -> Result<ViewRoute, super::ViewError> {
-    let client = Rect {
-      up_left: Default::default(),
-      size: self.renderer.output_size().unwrap(),
-    };
-
+    let palette = self.skin.palette;
     enum Dst {
       Game,
+      Back,
       Quit,
     }
     let will_navigate_to = Rc::new(RefCell::new(None));
-
-    let stats_dim = Rect {
-      up_left: Point {
-        x: 0,
-        y: client.size.height as i32 - 300,
-      },
-      size: Size {
-        width: client.size.width,
-        height: 200,
-      },
-    };
-    let stats = Stats::new(
-      StatsProps {
-        type_per_second: 0.0,
-        score: self.score.clone(),
-      },
-      Rc::clone(&self.font),
-      stats_dim,
-    );
-
-    let header_dim = Rect {
-      up_left: Point { x: 20, y: 50 },
-      size: Size {
-        width: client.size.width.saturating_sub(40),
-        height: 100,
-      },
-    };
-    let header = Header::new(
-      HeaderProps {
-        music_info: self.music_info.clone(),
-        score_point: self.score.score_point,
-        sentence_result: None,
-      },
-      Rc::clone(&self.font),
-      header_dim,
-    );
-
-    const WIDTH: u32 = 240;
-    const HEIGHT: u32 = 80;
-    const MARGIN: u32 = 20;
-    let retry_button_area = Rect {
-      up_left: Point {
-        x: client.size.width as i32 - WIDTH as i32 - MARGIN as i32,
-        y: client.size.height as i32 - HEIGHT as i32 - MARGIN as i32,
-      },
-      size: Size {
-        width: WIDTH,
-        height: HEIGHT,
-      },
-    };
-    let mut retry_button = Button::new(
-      ButtonProps {
-        border_color: 0x0a0d0a.into(),
-        color_on_hover: 0xdce0dc.into(),
-        mouse: None,
-      },
-      retry_button_area,
-      || {
-        will_navigate_to.borrow_mut().replace(Dst::Game);
-      },
-    );
-
+    let export_requested = Cell::new(false);
+    let mut export_message = None;
+    let tab = Cell::new(Tab::Summary);
     let should_quit = Cell::new(false);
     let mouse_event = Rc::new(RefCell::new(None));
 
     let mut event = EventBox::new(self.video);
 
     event.handle_quit(Box::new(|_| should_quit.set(true)));
-    event.handle_keyboard(Box::new(|_| should_quit.set(true)));
+    // 左右キーとタブキーで表示を切り替え、エンターで再挑戦する
+    event.handle_keyboard(Box::new(|e| {
+      if !e.is_pressed || e.is_repeated {
+        return;
+      }
+      match e.symbol.scan_code {
+        ScanCode::Left => tab.set(tab.get().prev()),
+        ScanCode::Right | ScanCode::Tab => tab.set(tab.get().next()),
+        ScanCode::Return => {
+          will_navigate_to.borrow_mut().replace(Dst::Game);
+        }
+        ScanCode::E => export_requested.set(true),
+        ScanCode::Escape => {
+          will_navigate_to.borrow_mut().replace(Dst::Back);
+        }
+        _ => {}
+      }
+    }));
     event.handle_mouse(Box::new(|mouse| {
       *mouse_event.borrow_mut() = Some(mouse.clone())
     }));
 
+    // ウィンドウの大きさが変わるたびに、部品を作り直して並べ直す
     loop {
-      if should_quit.get() {
-        will_navigate_to.borrow_mut().replace(Dst::Quit);
-      }
-      let time = Instant::now();
-      event.poll();
-      {
-        let pen = Pen::new(self.renderer);
-        pen.set_color(Rgb {
-          r: 253,
-          g: 243,
-          b: 226,
-        });
-        pen.clear();
-
-        header.render(&pen);
-        stats.render(&pen);
-
-        let new_props = ButtonProps {
-          border_color: 0x0a0d0a.into(),
-          color_on_hover: 0xdce0dc.into(),
-          mouse: mouse_event.borrow().clone(),
-        };
-        if retry_button.is_needed_redraw(&new_props) {
-          retry_button.update(new_props);
+      let layout = Layout::of(self.renderer);
+      let client = layout.client();
+
+      let rows = layout.rows(
+        client,
+        &[
+          Length::Fixed(50),
+          Length::Fixed(100),
+          Length::Fixed(10),
+          Length::Fixed(40),
+          Length::Fixed(10),
+          Length::Flex(1),
+          Length::Fixed(120),
+        ],
+      );
+      let inner = |row: Rect| {
+        layout.columns(
+          row,
+          &[Length::Fixed(20), Length::Flex(1), Length::Fixed(20)],
+        )[1]
+      };
+      let header_dim = inner(rows[1]);
+      let tab_areas = layout.columns(
+        inner(rows[3]),
+        &vec![Length::Flex(1); Tab::ALL.len()],
+      );
+      let breakdown_dim = inner(rows[5]);
+      let stats_dim = layout.rows(
+        client,
+        &[Length::Flex(1), Length::Fixed(200), Length::Fixed(100)],
+      )[1];
+      let retry_button_area =
+        layout.anchor(client, 240, 80, Anchor::BottomRight, 20);
+      let export_button_area =
+        layout.anchor(client, 240, 80, Anchor::BottomLeft, 20);
+
+      let stats = Stats::new(
+        StatsProps {
+          type_per_second: 0.0,
+          score: self.score.clone(),
+        },
+        Rc::clone(&self.font),
+        stats_dim,
+        layout,
+        palette,
+      );
+
+      let header = Header::new(
+        HeaderProps {
+          music_info: self.music_info.clone(),
+          score_point: self.score.score_point,
+          sentence_result: None,
+        },
+        Rc::clone(&self.font),
+        header_dim,
+        layout,
+        palette,
+      );
+
+      let mut retry_button = Button::new(
+        ButtonProps {
+          border_color: palette.border.into(),
+          color_on_hover: palette.hover.into(),
+          mouse: None,
+        },
+        retry_button_area,
+        || {
+          will_navigate_to.borrow_mut().replace(Dst::Game);
+        },
+      );
+
+      let mut export_button = Button::new(
+        ButtonProps {
+          border_color: palette.border.into(),
+          color_on_hover: palette.hover.into(),
+          mouse: None,
+        },
+        export_button_area,
+        || export_requested.set(true),
+      );
+
+      let mut tab_buttons: Vec<_> = Tab::ALL
+        .iter()
+        .zip(tab_areas.iter())
+        .map(|(&target, &area)| {
+          let tab = &tab;
+          let on_click: Box<dyn FnMut() + '_> =
+            Box::new(move || tab.set(target));
+          Button::new(
+            ButtonProps {
+              border_color: palette.border.into(),
+              color_on_hover: palette.hover.into(),
+              mouse: None,
+            },
+            area,
+            on_click,
+          )
+        })
+        .collect();
+
+      let mut breakdown = Breakdown::new(
+        BreakdownProps {
+          tab: tab.get(),
+          score: self.score.clone(),
+          log: self.log.clone(),
+        },
+        Rc::clone(&self.font),
+        breakdown_dim,
+        layout,
+        palette,
+      );
+
+      loop {
+        if should_quit.get() {
+          will_navigate_to.borrow_mut().replace(Dst::Quit);
         }
-        retry_button.render(&pen);
-
-        self.font.set_font_size(60).unwrap();
-        pen.text(
-          &self.font,
-          "再挑戦",
-          FontRenderOptions::new()
-            .mode(RenderMode::Blended {
-              foreground: Rgba {
-                r: 36,
-                g: 141,
-                b: 255,
-                a: 255,
-              },
-            })
-            .align(TextAlign {
-              x: TextAlignX::Center,
-              y: TextAlignY::Center,
-            })
-            .pivot(retry_button_area.center()),
-        );
-      }
+        let time = Instant::now();
+        event.poll();
+        if export_requested.replace(false) {
+          export_message = Some(self.export());
+        }
+        {
+          let pen = Pen::new(self.renderer);
+          pen.set_color(palette.background.into());
+          pen.clear();
 
-      let draw_time = time.elapsed().as_secs_f64();
-      delay((1e3 / 60.0 - draw_time * 1e3).max(0.0) as u32);
+          header.render(&pen);
+          if tab.get() == Tab::Summary {
+            stats.render(&pen);
+          }
 
-      let will_navigate_to = will_navigate_to.borrow();
-      if let Some(will_navigate_to) = will_navigate_to.as_ref() {
-        match will_navigate_to {
-          Dst::Game => return Ok(ViewRoute::Retry),
-          Dst::Quit => return Ok(ViewRoute::Quit),
+          for (index, button) in tab_buttons.iter_mut().enumerate() {
+            let area = tab_areas[index];
+            if Tab::ALL[index] == tab.get() {
+              pen.set_color(palette.hover.into());
+              pen.fill_rect(area);
+            }
+            let new_props = ButtonProps {
+              border_color: palette.border.into(),
+              color_on_hover: palette.hover.into(),
+              mouse: mouse_event.borrow().clone(),
+            };
+            if button.is_needed_redraw(&new_props) {
+              button.update(new_props);
+            }
+            button.render(&pen);
+            self.font.set_font_size(layout.font(20)).unwrap();
+            pen.text(
+              &self.font,
+              Tab::ALL[index].title(),
+              FontRenderOptions::new()
+                .mode(RenderMode::Blended {
+                  foreground: opaque(palette.text),
+                })
+                .align(TextAlign {
+                  x: TextAlignX::Center,
+                  y: TextAlignY::Center,
+                })
+                .pivot(area.center()),
+            );
+          }
+
+          let new_props = BreakdownProps {
+            tab: tab.get(),
+            score: self.score.clone(),
+            log: self.log.clone(),
+          };
+          if breakdown.is_needed_redraw(&new_props) {
+            breakdown.update(new_props);
+          }
+          breakdown.render(&pen);
+
+          let new_props = ButtonProps {
+            border_color: palette.border.into(),
+            color_on_hover: palette.hover.into(),
+            mouse: mouse_event.borrow().clone(),
+          };
+          if retry_button.is_needed_redraw(&new_props) {
+            retry_button.update(new_props);
+          }
+          retry_button.render(&pen);
+
+          let new_props = ButtonProps {
+            border_color: palette.border.into(),
+            color_on_hover: palette.hover.into(),
+            mouse: mouse_event.borrow().clone(),
+          };
+          if export_button.is_needed_redraw(&new_props) {
+            export_button.update(new_props);
+          }
+          export_button.render(&pen);
+
+          self.font.set_font_size(layout.font(40)).unwrap();
+          pen.text(
+            &self.font,
+            "書き出し",
+            FontRenderOptions::new()
+              .mode(RenderMode::Blended {
+                foreground: opaque(palette.highlight),
+              })
+              .align(TextAlign {
+                x: TextAlignX::Center,
+                y: TextAlignY::Center,
+              })
+              .pivot(export_button_area.center()),
+          );
+          if let Some(message) = &export_message {
+            self.font.set_font_size(layout.font(16)).unwrap();
+            pen.text(
+              &self.font,
+              message,
+              FontRenderOptions::new()
+                .mode(RenderMode::Blended {
+                  foreground: opaque(palette.text),
+                })
+                .align(TextAlign {
+                  x: TextAlignX::Center,
+                  y: TextAlignY::Center,
+                })
+                .pivot(Point {
+                  x: client.center().x,
+                  y: retry_button_area.center().y,
+                }),
+            );
+          }
+
+          self.font.set_font_size(layout.font(60)).unwrap();
+          pen.text(
+            &self.font,
+            "再挑戦",
+            FontRenderOptions::new()
+              .mode(RenderMode::Blended {
+                foreground: opaque(palette.highlight),
+              })
+              .align(TextAlign {
+                x: TextAlignX::Center,
+                y: TextAlignY::Center,
+              })
+              .pivot(retry_button_area.center()),
+          );
+        }
+
+        let draw_time = time.elapsed().as_secs_f64();
+        delay((1e3 / 60.0 - draw_time * 1e3).max(0.0) as u32);
+
+        if let Some(dst) = will_navigate_to.borrow().as_ref() {
+          match dst {
+            Dst::Game => return Ok(ViewRoute::Retry),
+            Dst::Back => return Ok(ViewRoute::Back),
+            Dst::Quit => return Ok(ViewRoute::Quit),
+          }
+        }
+        if layout.resized(self.renderer).is_some() {
+          break;
         }
       }
     }
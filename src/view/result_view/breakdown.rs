@@ -0,0 +1,229 @@
+use rich_sdl2_rust::ttf::font::{
+  pen::{FontRenderExt, FontRenderOptions},
+  Font, RenderMode, StyleExt,
+};
+use rich_sdl2_rust::{
+  geo::{Point, Rect, Size},
+  renderer::pen::Pen,
+};
+use std::rc::Rc;
+
+use crate::{
+  model::{
+    exp::game_activity::GameScore, game::PlayLog, skin::Palette,
+  },
+  view::{layout::Layout, opaque, Component},
+};
+
+const LINE_HEIGHT: i32 = 28;
+const SPEED_INTERVAL: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tab {
+  Summary,
+  Sections,
+  Missed,
+  Speed,
+}
+
+impl Tab {
+  pub const ALL: [Tab; 4] =
+    [Tab::Summary, Tab::Sections, Tab::Missed, Tab::Speed];
+
+  pub fn title(&self) -> &'static str {
+    match self {
+      Tab::Summary => "概要",
+      Tab::Sections => "セクション",
+      Tab::Missed => "ミスした文",
+      Tab::Speed => "打鍵速度",
+    }
+  }
+
+  fn index(&self) -> usize {
+    Self::ALL.iter().position(|tab| tab == self).unwrap()
+  }
+
+  pub fn next(&self) -> Self {
+    Self::ALL[(self.index() + 1) % Self::ALL.len()]
+  }
+
+  pub fn prev(&self) -> Self {
+    Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+  }
+}
+
+#[derive(PartialEq)]
+pub struct BreakdownProps {
+  pub tab: Tab,
+  pub score: GameScore,
+  pub log: PlayLog,
+}
+
+pub struct Breakdown<'font> {
+  props: BreakdownProps,
+  font: Rc<Font<'font>>,
+  client: Rect,
+  layout: Layout,
+  palette: Palette,
+}
+
+impl<'font> Breakdown<'font> {
+  pub fn new(
+    props: BreakdownProps,
+    font: Rc<Font<'font>>,
+    client: Rect,
+    layout: Layout,
+    palette: Palette,
+  ) -> Self {
+    Self {
+      props,
+      font,
+      client,
+      layout,
+      palette,
+    }
+  }
+
+  fn line_height(&self) -> i32 {
+    self.layout.px(LINE_HEIGHT).max(1)
+  }
+
+  // 入りきらない行は省いて、その数を最後に示す
+  fn render_lines(&self, pen: &Pen<'_>, lines: &[String]) {
+    let capacity = (self.client.size.height as i32
+      / self.line_height())
+    .max(1) as usize;
+    let (shown, omitted) = if capacity < lines.len() {
+      (&lines[..capacity - 1], lines.len() - (capacity - 1))
+    } else {
+      (lines, 0)
+    };
+    self.font.set_font_size(self.layout.font(20)).unwrap();
+    let omitted_line = format!("ほか {} 件", omitted);
+    let omitted_lines = [omitted_line];
+    let tail: &[String] =
+      if 0 < omitted { &omitted_lines } else { &[] };
+    for (index, line) in shown.iter().chain(tail).enumerate() {
+      pen.text(
+        &self.font,
+        line,
+        FontRenderOptions::new()
+          .mode(RenderMode::Blended {
+            foreground: opaque(self.palette.text),
+          })
+          .pivot(self.client.up_left.offset(
+            self.layout.px(10),
+            index as i32 * self.line_height(),
+          )),
+      );
+    }
+  }
+
+  fn render_speed(&self, pen: &Pen<'_>) {
+    let speeds =
+      self.props.log.speed_over_time(SPEED_INTERVAL.into());
+    if speeds.is_empty() {
+      self.render_lines(pen, &["打鍵の記録がありません".into()]);
+      return;
+    }
+    let max_speed = speeds.iter().cloned().fold(1.0, f64::max);
+    let graph_height =
+      self.client.size.height as i32 - self.line_height();
+    // 区間が横幅の画素数より多くても並ぶよう、棒の位置は小数で求める
+    let left = self.client.left() + self.layout.px(10);
+    let bar_width = (self.client.size.width as i32
+      - self.layout.px(20)) as f64
+      / speeds.len() as f64;
+    pen.set_color(self.palette.highlight.into());
+    for (index, speed) in speeds.iter().enumerate() {
+      let height = (graph_height as f64 * speed / max_speed) as i32;
+      if height == 0 {
+        continue;
+      }
+      let x = left + (index as f64 * bar_width) as i32;
+      let next_x = left + ((index + 1) as f64 * bar_width) as i32;
+      pen.fill_rect(Rect {
+        up_left: Point {
+          x,
+          y: self.client.bottom() - height,
+        },
+        size: Size {
+          width: (next_x - x).max(1) as u32,
+          height: height as u32,
+        },
+      });
+    }
+    self.render_lines(
+      pen,
+      &[format!(
+        "{} 秒ごとの打鍵速度 (最大 {:.1} 打/秒)",
+        SPEED_INTERVAL, max_speed
+      )],
+    );
+  }
+}
+
+impl<'font> Component for Breakdown<'font> {
+  type Props = BreakdownProps;
+
+  fn is_needed_redraw(&self, new_props: &Self::Props) -> bool {
+    &self.props != new_props
+  }
+
+  fn update(&mut self, new_props: Self::Props) {
+    self.props = new_props;
+  }
+
+  fn render(&self, pen: &Pen<'_>) {
+    let BreakdownProps { tab, score, log } = &self.props;
+    match tab {
+      Tab::Summary => self.render_lines(
+        pen,
+        &[
+          format!(
+            "最大コンボ {}    ミスした文 {}",
+            score.max_combo,
+            log.missed_sentences().len()
+          ),
+          format!(
+            "正しい打鍵 {}    誤った打鍵 {}",
+            score.correction_type_count(),
+            score.wrong_type_count()
+          ),
+        ],
+      ),
+      Tab::Sections => {
+        let lines: Vec<_> = log
+          .sections()
+          .iter()
+          .filter(|stats| 0 < stats.sentences)
+          .map(|stats| {
+            format!(
+              "セクション {}    正確さ {:.1}%    完了 {} / {}",
+              stats.index + 1,
+              stats.accuracy * 100.0,
+              stats.completed_sentences,
+              stats.sentences
+            )
+          })
+          .collect();
+        if lines.is_empty() {
+          self.render_lines(
+            pen,
+            &["セクションの記録がありません".into()],
+          );
+        } else {
+          self.render_lines(pen, &lines);
+        }
+      }
+      Tab::Missed => {
+        if log.missed_sentences().is_empty() {
+          self.render_lines(pen, &["ミスした文はありません".into()]);
+        } else {
+          self.render_lines(pen, log.missed_sentences());
+        }
+      }
+      Tab::Speed => self.render_speed(pen),
+    }
+  }
+}
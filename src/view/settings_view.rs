@@ -0,0 +1,661 @@
+use rich_sdl2_rust::ttf::font::{
+  pen::{
+    FontRenderExt, FontRenderOptions, TextAlign, TextAlignX,
+    TextAlignY,
+  },
+  Font, RenderMode, StyleExt,
+};
+use rich_sdl2_rust::{
+  delay,
+  event::keyboard::scan_code::ScanCode,
+  geo::Point,
+  renderer::{pen::Pen, Renderer},
+  EventBox, Video,
+};
+use std::{
+  cell::{Cell, RefCell},
+  path::Path,
+  rc::Rc,
+  time::Instant,
+};
+
+use super::{
+  components::{
+    Button, ButtonProps, Choice, ChoiceProps, Slider, SliderProps,
+    Toggle, ToggleProps,
+  },
+  layout::{Anchor, Layout, Length},
+  opaque, View, ViewError, ViewRoute,
+};
+use crate::{
+  model::{
+    exp::{sentence::roman::RomanBaseStyle, time::Seconds},
+    keyboard_layout::KeyboardLayout,
+    settings::{Settings, WINDOW_SIZES},
+    skin::{skin_names, DisplayMode, Skin, SKINS_DIR},
+  },
+  view::Component,
+};
+
+const ROWS: [&str; 11] = [
+  "音量",
+  "ローマ字",
+  "スキン",
+  "表示",
+  "ずれ",
+  "ウィンドウ",
+  "全画面",
+  "カウントダウン",
+  "綴りの候補",
+  "キーボード",
+  "指の色",
+];
+const ROMAN_STYLES: [RomanBaseStyle; 3] = [
+  RomanBaseStyle::Default,
+  RomanBaseStyle::Hepburn,
+  RomanBaseStyle::Kunrei,
+];
+// ずれはスライダーで ±300 ms の範囲を選べる
+const OFFSET_RANGE_MS: i32 = 300;
+const ROW_TOP: u32 = 60;
+const ROW_HEIGHT: u32 = 40;
+const LABEL_WIDTH: u32 = 260;
+const MARGIN: u32 = 40;
+
+fn cycle<T: PartialEq + Clone>(
+  items: &[T],
+  current: &T,
+  delta: i32,
+) -> T {
+  let index =
+    items.iter().position(|item| item == current).unwrap_or(0) as i32;
+  let len = items.len() as i32;
+  items[(index + delta).rem_euclid(len) as usize].clone()
+}
+
+fn offset_ms(settings: &Settings) -> i32 {
+  (settings.user_offset.as_f64() * 1e3).round() as i32
+}
+
+fn set_offset_ms(settings: &mut Settings, ms: i32) {
+  let ms = ms.clamp(-OFFSET_RANGE_MS, OFFSET_RANGE_MS);
+  settings.user_offset = Seconds::new(ms as f64 / 1e3);
+}
+
+// 左右キーで、選んでいる行の値を変える
+fn adjust(
+  settings: &mut Settings,
+  skins: &[String],
+  row: usize,
+  delta: i32,
+) {
+  match row {
+    0 => {
+      settings.volume =
+        (settings.volume as i32 + delta * 5).clamp(0, 100) as u32;
+    }
+    1 => {
+      settings.roman_style =
+        cycle(&ROMAN_STYLES, &settings.roman_style, delta)
+    }
+    2 => settings.skin = cycle(skins, &settings.skin, delta),
+    3 => {
+      settings.display_mode =
+        cycle(&DisplayMode::ALL, &settings.display_mode, delta)
+    }
+    4 => {
+      let ms = offset_ms(settings) + delta * 5;
+      set_offset_ms(settings, ms);
+    }
+    5 => {
+      settings.window_size =
+        cycle(&WINDOW_SIZES, &settings.window_size, delta)
+    }
+    6 => settings.fullscreen = !settings.fullscreen,
+    7 => settings.countdown = !settings.countdown,
+    8 => settings.spelling_hints = !settings.spelling_hints,
+    9 => {
+      settings.keyboard_layout =
+        cycle(&KeyboardLayout::ALL, &settings.keyboard_layout, delta)
+    }
+    10 => settings.finger_guide = !settings.finger_guide,
+    _ => {}
+  }
+}
+
+fn roman_title(style: RomanBaseStyle) -> &'static str {
+  match style {
+    RomanBaseStyle::Default => "標準",
+    RomanBaseStyle::Hepburn => "ヘボン式",
+    RomanBaseStyle::Kunrei => "訓令式",
+  }
+}
+
+pub struct SettingsView<'view> {
+  renderer: &'view Renderer<'view>,
+  font: Rc<Font<'view>>,
+  video: &'view Video<'view>,
+  settings: Settings,
+  skin: Skin,
+  // スキンの名前と、設定画面に出す題
+  skins: Vec<(String, String)>,
+}
+
+impl<'view> SettingsView<'view> {
+  pub fn new(
+    renderer: &'view Renderer<'view>,
+    font: Rc<Font<'view>>,
+    video: &'view Video<'view>,
+    settings: Settings,
+    skin: Skin,
+  ) -> Self {
+    let skins_dir = Path::new(SKINS_DIR);
+    let skins = skin_names(skins_dir)
+      .into_iter()
+      .map(|name| {
+        let title = Skin::load(skins_dir, &name)
+          .map_or_else(|_| name.clone(), |skin| skin.title);
+        (name, title)
+      })
+      .collect();
+    Self {
+      renderer,
+      font,
+      video,
+      settings,
+      skin,
+      skins,
+    }
+  }
+}
+
+impl<'view> View for SettingsView<'view> {
+  fn run(&mut self) -> Result<ViewRoute, ViewError> {
+    let palette = self.skin.palette;
+    let skin_names: Vec<_> =
+      self.skins.iter().map(|(name, _)| name.clone()).collect();
+    let settings = RefCell::new(self.settings.clone());
+    let focus = Cell::new(0usize);
+    let should_close = Cell::new(false);
+    // エスケープキーで閉じたときは、変えた内容を捨てる
+    let should_discard = Cell::new(false);
+    let should_quit = Cell::new(false);
+    let mouse_event = Rc::new(RefCell::new(None));
+    let mouse = || mouse_event.borrow().clone();
+
+    let mut event = EventBox::new(self.video);
+    event.handle_quit(Box::new(|_| should_quit.set(true)));
+    // 上下キーで行を選び、左右キーで値を変える
+    event.handle_keyboard(Box::new(|e| {
+      if !e.is_pressed {
+        return;
+      }
+      match e.symbol.scan_code {
+        ScanCode::Up => {
+          focus.set((focus.get() + ROWS.len() - 1) % ROWS.len())
+        }
+        ScanCode::Down => focus.set((focus.get() + 1) % ROWS.len()),
+        ScanCode::Left => adjust(
+          &mut settings.borrow_mut(),
+          &skin_names,
+          focus.get(),
+          -1,
+        ),
+        ScanCode::Right => adjust(
+          &mut settings.borrow_mut(),
+          &skin_names,
+          focus.get(),
+          1,
+        ),
+        // 前の画面で押したままのキーのリピートでは閉じない
+        ScanCode::Return if !e.is_repeated => should_close.set(true),
+        ScanCode::Escape if !e.is_repeated => {
+          should_discard.set(true)
+        }
+        _ => {}
+      }
+    }));
+    event.handle_mouse(Box::new(|mouse| {
+      *mouse_event.borrow_mut() = Some(mouse.clone())
+    }));
+
+    // ウィンドウの大きさが変わるたびに、部品を作り直して並べ直す
+    loop {
+      let layout = Layout::of(self.renderer);
+      let client = layout.client();
+
+      let mut lengths = vec![Length::Fixed(ROW_TOP)];
+      lengths.extend(ROWS.iter().map(|_| Length::Fixed(ROW_HEIGHT)));
+      lengths.extend([Length::Fixed(30), Length::Flex(1)]);
+      let content = layout.columns(
+        client,
+        &[
+          Length::Fixed(MARGIN),
+          Length::Flex(1),
+          Length::Fixed(MARGIN),
+        ],
+      )[1];
+      let rows = layout.rows(content, &lengths);
+      let row_area = |row: usize| rows[row + 1];
+      let note_area = rows[ROWS.len() + 1];
+      let widget_area = |row: usize| {
+        let widget = layout.columns(
+          row_area(row),
+          &[Length::Fixed(LABEL_WIDTH), Length::Flex(1)],
+        )[1];
+        layout.rows(
+          widget,
+          &[Length::Fixed(8), Length::Flex(1), Length::Fixed(8)],
+        )[1]
+      };
+      let toggle_area = |row: usize| {
+        layout.columns(
+          widget_area(row),
+          &[Length::Fixed(80), Length::Flex(1)],
+        )[0]
+      };
+
+      let mut volume = Slider::new(
+        SliderProps {
+          value: settings.borrow().volume,
+          max: 100,
+          mouse: None,
+        },
+        widget_area(0),
+        palette,
+        |value| settings.borrow_mut().volume = value,
+      );
+      let mut roman = Choice::new(
+        ChoiceProps {
+          options: ROMAN_STYLES
+            .iter()
+            .map(|&style| roman_title(style).into())
+            .collect(),
+          selected: 0,
+          mouse: None,
+        },
+        Rc::clone(&self.font),
+        widget_area(1),
+        layout,
+        palette,
+        |index| {
+          settings.borrow_mut().roman_style = ROMAN_STYLES[index]
+        },
+      );
+      let mut skin = Choice::new(
+        ChoiceProps {
+          options: self
+            .skins
+            .iter()
+            .map(|(_, title)| title.clone())
+            .collect(),
+          selected: 0,
+          mouse: None,
+        },
+        Rc::clone(&self.font),
+        widget_area(2),
+        layout,
+        palette,
+        |index| {
+          settings.borrow_mut().skin = skin_names[index].clone()
+        },
+      );
+      let mut display_mode = Choice::new(
+        ChoiceProps {
+          options: DisplayMode::ALL
+            .iter()
+            .map(|mode| mode.title().into())
+            .collect(),
+          selected: 0,
+          mouse: None,
+        },
+        Rc::clone(&self.font),
+        widget_area(3),
+        layout,
+        palette,
+        |index| {
+          settings.borrow_mut().display_mode = DisplayMode::ALL[index]
+        },
+      );
+      let mut offset = Slider::new(
+        SliderProps {
+          value: 0,
+          max: OFFSET_RANGE_MS as u32 * 2,
+          mouse: None,
+        },
+        widget_area(4),
+        palette,
+        |value| {
+          set_offset_ms(
+            &mut settings.borrow_mut(),
+            value as i32 - OFFSET_RANGE_MS,
+          )
+        },
+      );
+      let mut window_size = Choice::new(
+        ChoiceProps {
+          options: WINDOW_SIZES
+            .iter()
+            .map(|(width, height)| format!("{}x{}", width, height))
+            .collect(),
+          selected: 0,
+          mouse: None,
+        },
+        Rc::clone(&self.font),
+        widget_area(5),
+        layout,
+        palette,
+        |index| {
+          settings.borrow_mut().window_size = WINDOW_SIZES[index]
+        },
+      );
+      let mut fullscreen = Toggle::new(
+        ToggleProps {
+          on: settings.borrow().fullscreen,
+          mouse: None,
+        },
+        toggle_area(6),
+        palette,
+        |on| settings.borrow_mut().fullscreen = on,
+      );
+      let mut countdown = Toggle::new(
+        ToggleProps {
+          on: settings.borrow().countdown,
+          mouse: None,
+        },
+        toggle_area(7),
+        palette,
+        |on| settings.borrow_mut().countdown = on,
+      );
+      let mut spelling_hints = Toggle::new(
+        ToggleProps {
+          on: settings.borrow().spelling_hints,
+          mouse: None,
+        },
+        toggle_area(8),
+        palette,
+        |on| settings.borrow_mut().spelling_hints = on,
+      );
+      let mut keyboard_layout = Choice::new(
+        ChoiceProps {
+          options: KeyboardLayout::ALL
+            .iter()
+            .map(|key_layout| key_layout.title().into())
+            .collect(),
+          selected: 0,
+          mouse: None,
+        },
+        Rc::clone(&self.font),
+        widget_area(9),
+        layout,
+        palette,
+        |index| {
+          settings.borrow_mut().keyboard_layout =
+            KeyboardLayout::ALL[index]
+        },
+      );
+      let mut finger_guide = Toggle::new(
+        ToggleProps {
+          on: settings.borrow().finger_guide,
+          mouse: None,
+        },
+        toggle_area(10),
+        palette,
+        |on| settings.borrow_mut().finger_guide = on,
+      );
+
+      let back_button_area =
+        layout.anchor(client, 200, 50, Anchor::BottomRight, 10);
+      let mut back_button = Button::new(
+        ButtonProps {
+          border_color: palette.border.into(),
+          color_on_hover: palette.hover.into(),
+          mouse: None,
+        },
+        back_button_area,
+        || should_close.set(true),
+      );
+
+      loop {
+        if should_quit.get() {
+          return Ok(ViewRoute::Quit);
+        }
+        if should_discard.get() {
+          return Ok(ViewRoute::Back);
+        }
+        if should_close.get() {
+          return Ok(ViewRoute::SavedSettings(
+            settings.borrow().clone(),
+          ));
+        }
+        let time = Instant::now();
+        event.poll();
+
+        let new_props = SliderProps {
+          value: settings.borrow().volume,
+          max: 100,
+          mouse: mouse(),
+        };
+        if volume.is_needed_redraw(&new_props) {
+          volume.update(new_props);
+        }
+        let new_props = ChoiceProps {
+          options: ROMAN_STYLES
+            .iter()
+            .map(|&style| roman_title(style).into())
+            .collect(),
+          selected: ROMAN_STYLES
+            .iter()
+            .position(|&style| style == settings.borrow().roman_style)
+            .unwrap_or(0),
+          mouse: mouse(),
+        };
+        if roman.is_needed_redraw(&new_props) {
+          roman.update(new_props);
+        }
+        let new_props = ChoiceProps {
+          options: self
+            .skins
+            .iter()
+            .map(|(_, title)| title.clone())
+            .collect(),
+          selected: skin_names
+            .iter()
+            .position(|name| *name == settings.borrow().skin)
+            .unwrap_or(0),
+          mouse: mouse(),
+        };
+        if skin.is_needed_redraw(&new_props) {
+          skin.update(new_props);
+        }
+        let new_props = ChoiceProps {
+          options: DisplayMode::ALL
+            .iter()
+            .map(|mode| mode.title().into())
+            .collect(),
+          selected: DisplayMode::ALL
+            .iter()
+            .position(|&mode| mode == settings.borrow().display_mode)
+            .unwrap_or(0),
+          mouse: mouse(),
+        };
+        if display_mode.is_needed_redraw(&new_props) {
+          display_mode.update(new_props);
+        }
+        let new_props = SliderProps {
+          value: (offset_ms(&settings.borrow()) + OFFSET_RANGE_MS)
+            .clamp(0, OFFSET_RANGE_MS * 2) as u32,
+          max: OFFSET_RANGE_MS as u32 * 2,
+          mouse: mouse(),
+        };
+        if offset.is_needed_redraw(&new_props) {
+          offset.update(new_props);
+        }
+        let new_props = ChoiceProps {
+          options: WINDOW_SIZES
+            .iter()
+            .map(|(width, height)| format!("{}x{}", width, height))
+            .collect(),
+          selected: WINDOW_SIZES
+            .iter()
+            .position(|&size| size == settings.borrow().window_size)
+            .unwrap_or(0),
+          mouse: mouse(),
+        };
+        if window_size.is_needed_redraw(&new_props) {
+          window_size.update(new_props);
+        }
+        let new_props = ToggleProps {
+          on: settings.borrow().fullscreen,
+          mouse: mouse(),
+        };
+        if fullscreen.is_needed_redraw(&new_props) {
+          fullscreen.update(new_props);
+        }
+        let new_props = ToggleProps {
+          on: settings.borrow().countdown,
+          mouse: mouse(),
+        };
+        if countdown.is_needed_redraw(&new_props) {
+          countdown.update(new_props);
+        }
+        let new_props = ToggleProps {
+          on: settings.borrow().spelling_hints,
+          mouse: mouse(),
+        };
+        if spelling_hints.is_needed_redraw(&new_props) {
+          spelling_hints.update(new_props);
+        }
+        let new_props = ChoiceProps {
+          options: KeyboardLayout::ALL
+            .iter()
+            .map(|key_layout| key_layout.title().into())
+            .collect(),
+          selected: KeyboardLayout::ALL
+            .iter()
+            .position(|&key_layout| {
+              key_layout == settings.borrow().keyboard_layout
+            })
+            .unwrap_or(0),
+          mouse: mouse(),
+        };
+        if keyboard_layout.is_needed_redraw(&new_props) {
+          keyboard_layout.update(new_props);
+        }
+        let new_props = ToggleProps {
+          on: settings.borrow().finger_guide,
+          mouse: mouse(),
+        };
+        if finger_guide.is_needed_redraw(&new_props) {
+          finger_guide.update(new_props);
+        }
+        let new_props = ButtonProps {
+          border_color: palette.border.into(),
+          color_on_hover: palette.hover.into(),
+          mouse: mouse(),
+        };
+        if back_button.is_needed_redraw(&new_props) {
+          back_button.update(new_props);
+        }
+
+        {
+          let current = settings.borrow().clone();
+          let pen = Pen::new(self.renderer);
+          pen.set_color(palette.background.into());
+          pen.clear();
+
+          let text_options = |pivot: Point, align_x: TextAlignX| {
+            FontRenderOptions::new()
+              .mode(RenderMode::Blended {
+                foreground: opaque(palette.text),
+              })
+              .align(TextAlign {
+                x: align_x,
+                y: TextAlignY::Center,
+              })
+              .pivot(pivot)
+          };
+
+          self.font.set_font_size(layout.font(40)).unwrap();
+          pen.text(
+            &self.font,
+            "設定",
+            text_options(
+              Point {
+                x: rows[0].left(),
+                y: rows[0].center().y,
+              },
+              TextAlignX::Left,
+            ),
+          );
+
+          pen.set_color(palette.hover.into());
+          pen.stroke_rect(row_area(focus.get()));
+
+          self.font.set_font_size(layout.font(24)).unwrap();
+          for (row, label) in ROWS.iter().enumerate() {
+            let area = row_area(row);
+            let value = match row {
+              0 => format!("{}%", current.volume),
+              4 => format!("{:+} ms", offset_ms(&current)),
+              _ => String::new(),
+            };
+            pen.text(
+              &self.font,
+              &format!("{} {}", label, value),
+              text_options(
+                Point {
+                  x: area.left() + layout.px(10),
+                  y: area.center().y,
+                },
+                TextAlignX::Left,
+              ),
+            );
+          }
+          volume.render(&pen);
+          roman.render(&pen);
+          skin.render(&pen);
+          display_mode.render(&pen);
+          offset.render(&pen);
+          window_size.render(&pen);
+          fullscreen.render(&pen);
+          countdown.render(&pen);
+          spelling_hints.render(&pen);
+          keyboard_layout.render(&pen);
+          finger_guide.render(&pen);
+
+          self.font.set_font_size(layout.font(16)).unwrap();
+          pen.text(
+            &self.font,
+            "ローマ字とウィンドウの大きさ、全画面、スキンの字体は、次に起動したときから反映されます",
+            text_options(
+              Point {
+                x: note_area.left(),
+                y: note_area.center().y,
+              },
+              TextAlignX::Left,
+            ),
+          );
+
+          back_button.render(&pen);
+          self.font.set_font_size(layout.font(28)).unwrap();
+          pen.text(
+            &self.font,
+            "戻る",
+            text_options(
+              back_button_area.center(),
+              TextAlignX::Center,
+            ),
+          );
+        }
+
+        let draw_time = time.elapsed().as_secs_f64();
+        delay((1e3 / 60.0 - draw_time * 1e3).max(0.0) as u32);
+
+        if layout.resized(self.renderer).is_some() {
+          break;
+        }
+      }
+    }
+  }
+}
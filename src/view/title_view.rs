@@ -0,0 +1,253 @@
+use rich_sdl2_rust::ttf::font::{
+  pen::{
+    FontRenderExt, FontRenderOptions, TextAlign, TextAlignX,
+    TextAlignY,
+  },
+  Font, RenderMode, StyleExt,
+};
+use rich_sdl2_rust::{
+  delay,
+  event::keyboard::scan_code::ScanCode,
+  geo::Point,
+  renderer::{pen::Pen, Renderer},
+  EventBox, Video,
+};
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
+  time::Instant,
+};
+
+use super::{
+  components::{Button, ButtonProps},
+  layout::{Anchor, Layout, Length},
+  opaque, View, ViewError, ViewRoute,
+};
+use crate::{
+  model::{exp::scoremap::MusicInfo, skin::Skin},
+  view::Component,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Menu {
+  Play,
+  Practice,
+  Settings,
+  Quit,
+}
+
+impl Menu {
+  const ALL: [Menu; 4] =
+    [Menu::Play, Menu::Practice, Menu::Settings, Menu::Quit];
+
+  fn title(&self) -> &'static str {
+    match self {
+      Menu::Play => "プレイ",
+      Menu::Practice => "練習",
+      Menu::Settings => "設定",
+      Menu::Quit => "終了",
+    }
+  }
+
+  fn route(&self) -> ViewRoute {
+    match self {
+      Menu::Play => ViewRoute::Play,
+      Menu::Practice => ViewRoute::Practice,
+      Menu::Settings => ViewRoute::Settings,
+      Menu::Quit => ViewRoute::Quit,
+    }
+  }
+}
+
+const BUTTON_WIDTH: u32 = 320;
+const BUTTON_HEIGHT: u32 = 60;
+const BUTTON_GAP: u32 = 16;
+
+pub struct TitleView<'view> {
+  renderer: &'view Renderer<'view>,
+  music_info: MusicInfo,
+  font: Rc<Font<'view>>,
+  video: &'view Video<'view>,
+  skin: Skin,
+}
+
+impl<'view> TitleView<'view> {
+  pub fn new(
+    renderer: &'view Renderer<'view>,
+    music_info: MusicInfo,
+    font: Rc<Font<'view>>,
+    video: &'view Video<'view>,
+    skin: Skin,
+  ) -> Self {
+    Self {
+      renderer,
+      music_info,
+      font,
+      video,
+      skin,
+    }
+  }
+}
+
+impl<'view> View for TitleView<'view> {
+  fn run(&mut self) -> Result<ViewRoute, ViewError> {
+    let palette = self.skin.palette;
+    let selected = Cell::new(None);
+    let focus = Cell::new(0usize);
+    let mouse_event = Rc::new(RefCell::new(None));
+
+    let mut event = EventBox::new(self.video);
+    event.handle_quit(Box::new(|_| selected.set(Some(Menu::Quit))));
+    event.handle_keyboard(Box::new(|e| {
+      // 前の画面で押した Enter のキーリピートで、すぐに選ばないようにする
+      if !e.is_pressed || e.is_repeated {
+        return;
+      }
+      let len = Menu::ALL.len();
+      match e.symbol.scan_code {
+        ScanCode::Up => focus.set((focus.get() + len - 1) % len),
+        ScanCode::Down => focus.set((focus.get() + 1) % len),
+        ScanCode::Return => {
+          selected.set(Some(Menu::ALL[focus.get()]))
+        }
+        ScanCode::Escape => selected.set(Some(Menu::Quit)),
+        _ => {}
+      }
+    }));
+    event.handle_mouse(Box::new(|mouse| {
+      *mouse_event.borrow_mut() = Some(mouse.clone())
+    }));
+
+    loop {
+      let layout = Layout::of(self.renderer);
+      let client = layout.client();
+
+      // ボタンは画面の下半分に縦に並べる
+      let menu_height =
+        (BUTTON_HEIGHT + BUTTON_GAP) * Menu::ALL.len() as u32;
+      let menu_row = layout.rows(
+        client,
+        &[
+          Length::Flex(1),
+          Length::Fixed(menu_height),
+          Length::Fixed(BUTTON_GAP),
+        ],
+      )[1];
+      let menu_area = layout.anchor(
+        menu_row,
+        BUTTON_WIDTH,
+        menu_height,
+        Anchor::Center,
+        0,
+      );
+      let button_areas: Vec<_> = layout
+        .rows(
+          menu_area,
+          &Menu::ALL
+            .iter()
+            .flat_map(|_| {
+              [
+                Length::Fixed(BUTTON_HEIGHT),
+                Length::Fixed(BUTTON_GAP),
+              ]
+            })
+            .collect::<Vec<_>>(),
+        )
+        .into_iter()
+        .step_by(2)
+        .collect();
+      let mut buttons: Vec<_> = Menu::ALL
+        .iter()
+        .zip(button_areas.iter())
+        .map(|(&menu, &area)| {
+          let selected = &selected;
+          let on_click: Box<dyn FnMut() + '_> =
+            Box::new(move || selected.set(Some(menu)));
+          Button::new(
+            ButtonProps {
+              border_color: palette.border.into(),
+              color_on_hover: palette.hover.into(),
+              mouse: None,
+            },
+            area,
+            on_click,
+          )
+        })
+        .collect();
+
+      loop {
+        if let Some(menu) = selected.get() {
+          return Ok(menu.route());
+        }
+        let time = Instant::now();
+        event.poll();
+        {
+          let pen = Pen::new(self.renderer);
+          pen.set_color(palette.background.into());
+          pen.clear();
+
+          let text_options = |pivot: Point| {
+            FontRenderOptions::new()
+              .mode(RenderMode::Blended {
+                foreground: opaque(palette.text),
+              })
+              .align(TextAlign {
+                x: TextAlignX::Center,
+                y: TextAlignY::Center,
+              })
+              .pivot(pivot)
+          };
+          let title_pivot = Point {
+            x: client.center().x,
+            y: client.size.height as i32 / 6,
+          };
+          self.font.set_font_size(layout.font(60)).unwrap();
+          pen.text(
+            &self.font,
+            "Musical Typer",
+            text_options(title_pivot),
+          );
+          self.font.set_font_size(layout.font(28)).unwrap();
+          pen.text(
+            &self.font,
+            &format!(
+              "{} / {}",
+              self.music_info.title, self.music_info.song_author
+            ),
+            text_options(layout.offset(title_pivot, 0, 70)),
+          );
+
+          for (index, button) in buttons.iter_mut().enumerate() {
+            let area = button_areas[index];
+            if index == focus.get() {
+              pen.set_color(palette.hover.into());
+              pen.fill_rect(area);
+            }
+            let new_props = ButtonProps {
+              border_color: palette.border.into(),
+              color_on_hover: palette.hover.into(),
+              mouse: mouse_event.borrow().clone(),
+            };
+            if button.is_needed_redraw(&new_props) {
+              button.update(new_props);
+            }
+            button.render(&pen);
+            self.font.set_font_size(layout.font(32)).unwrap();
+            pen.text(
+              &self.font,
+              Menu::ALL[index].title(),
+              text_options(area.center()),
+            );
+          }
+        }
+
+        let draw_time = time.elapsed().as_secs_f64();
+        delay((1e3 / 60.0 - draw_time * 1e3).max(0.0) as u32);
+
+        if layout.resized(self.renderer).is_some() {
+          break;
+        }
+      }
+    }
+  }
+}